@@ -0,0 +1,703 @@
+// Copyright (c) 2026 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+use std::{
+    fmt::Write,
+    fs::File,
+    io::BufWriter,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use anc_image::{
+    bytecode_reader::format_bytecode_as_text,
+    entry::{ImageCommonEntry, ImageIndexEntry},
+    entry_reader::{read_image_file, read_object_file},
+    entry_writer::{write_image_file, write_object_file},
+    module_image::ImageType,
+};
+use anc_isa::EffectiveVersion;
+use anc_linker::{
+    dependency_graph::format_module_location,
+    elf_verifier::verify_external_functions,
+    module_provider::{load_and_link_application, FileSystemModuleProvider},
+    static_linker::StaticLinker,
+    system_library::get_default_library_search_directories,
+    ExternalSymbolNamespace, LinkErrorType, LinkerError, LinkerWarning,
+};
+
+const USAGE: &str = "\
+Usage:
+    anc-link static <OUTPUT> <INPUT>... [OPTIONS]
+        Links object files into a shared module (or an application).
+
+        --name <NAME>            the module name, defaults to the output file name
+        --version <VERSION>      the module version, defaults to \"0.0.0\"
+        --type <TYPE>            \"shared\" (default), \"application\" or \"object\"
+        --strip                  removes the relocations and the unused types
+        --inline                 inlines the trivial getters and forwarding wrappers
+        --optimize-data-layout   reorders the data to reduce the padding
+        --remove-unused          removes the unreachable private functions
+        --export <PATTERN>       keeps the matched exports only, '*' is the wildcard
+        --flag <FLAG>            the active feature or platform flag
+        --global-namespace       identifies external functions by name only
+        --verify-external        checks that the external functions are exported
+                                 by the \"Local\" and \"System\" libraries
+        --system-library-dir <DIR>
+                                 the directory for finding \"System\" libraries
+                                 when verifying, defaults to the standard ones
+        --warnings-as-errors     fails on the first warning
+
+    anc-link dynamic <OUTPUT> <APPLICATION> [OPTIONS]
+        Loads the dependent modules of the application and writes
+        the application with the index to the output.
+
+        --module-dir <DIR>       the directory of modules, defaults to the
+                                 directory of the application
+        --flag <FLAG>            the active feature or platform flag
+        --global-namespace       identifies external functions by name only
+        --system-library-dir <DIR>
+                                 the directory for resolving \"System\" libraries
+        --resolve-system-libraries
+                                 resolves \"System\" libraries with the
+                                 default search directories
+        --verify-external        checks that the external functions are exported
+                                 by the \"Local\" and \"System\" libraries
+        --warnings-as-errors     fails on the first warning
+
+    anc-link inspect <FILE> [--code]
+        Prints the content of the image file.";
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    let result = match args.first().map(|item| item.as_str()) {
+        Some("static") => run_static(&args[1..]),
+        Some("dynamic") => run_dynamic(&args[1..]),
+        Some("inspect") => run_inspect(&args[1..]),
+        Some("-h") | Some("--help") | Some("help") => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        _ => Err(format!("Missing or unknown subcommand.\n\n{}", USAGE)),
+    };
+
+    match result {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// The positional arguments and options of a subcommand.
+#[derive(Default)]
+struct Arguments {
+    positionals: Vec<String>,
+    options: Vec<(/* name */ String, /* value */ Option<String>)>,
+}
+
+impl Arguments {
+    fn parse(
+        args: &[String],
+        value_option_names: &[&str],
+        switch_option_names: &[&str],
+    ) -> Result<Self, String> {
+        let mut arguments = Arguments::default();
+        let mut arg_iter = args.iter();
+
+        while let Some(arg) = arg_iter.next() {
+            let Some(option_name) = arg.strip_prefix("--") else {
+                arguments.positionals.push(arg.to_owned());
+                continue;
+            };
+
+            if value_option_names.contains(&option_name) {
+                let value = arg_iter
+                    .next()
+                    .ok_or_else(|| format!("Missing the value of option \"{}\".", arg))?;
+                arguments
+                    .options
+                    .push((option_name.to_owned(), Some(value.to_owned())));
+            } else if switch_option_names.contains(&option_name) {
+                arguments.options.push((option_name.to_owned(), None));
+            } else {
+                return Err(format!("Unknown option \"{}\".", arg));
+            }
+        }
+
+        Ok(arguments)
+    }
+
+    fn get_values(&self, option_name: &str) -> Vec<&str> {
+        self.options
+            .iter()
+            .filter(|(name, _)| name == option_name)
+            .filter_map(|(_, value_opt)| value_opt.as_deref())
+            .collect()
+    }
+
+    fn get_value(&self, option_name: &str) -> Option<&str> {
+        self.get_values(option_name).last().copied()
+    }
+
+    fn is_set(&self, option_name: &str) -> bool {
+        self.options.iter().any(|(name, _)| name == option_name)
+    }
+
+    fn get_system_library_search_directories(&self) -> Vec<PathBuf> {
+        self.get_values("system-library-dir")
+            .iter()
+            .map(PathBuf::from)
+            .collect()
+    }
+
+    fn get_external_symbol_namespace(&self) -> ExternalSymbolNamespace {
+        if self.is_set("global-namespace") {
+            ExternalSymbolNamespace::Global
+        } else {
+            ExternalSymbolNamespace::LibraryQualified
+        }
+    }
+}
+
+fn run_static(args: &[String]) -> Result<(), String> {
+    let arguments = Arguments::parse(
+        args,
+        &[
+            "name",
+            "version",
+            "type",
+            "export",
+            "flag",
+            "system-library-dir",
+        ],
+        &[
+            "strip",
+            "inline",
+            "optimize-data-layout",
+            "remove-unused",
+            "global-namespace",
+            "verify-external",
+            "warnings-as-errors",
+        ],
+    )?;
+
+    let [output_path, input_paths @ ..] = arguments.positionals.as_slice() else {
+        return Err("Missing the output file.".to_owned());
+    };
+
+    if input_paths.is_empty() {
+        return Err("Missing the input files.".to_owned());
+    }
+
+    let image_type = match arguments.get_value("type").unwrap_or("shared") {
+        "shared" => ImageType::SharedModule,
+        "application" => ImageType::Application,
+        "object" => ImageType::ObjectFile,
+        other => return Err(format!("Unknown image type \"{}\".", other)),
+    };
+
+    let module_name = match arguments.get_value("name") {
+        Some(name) => name.to_owned(),
+        None => get_file_stem(Path::new(output_path))?,
+    };
+    let module_version =
+        EffectiveVersion::from_str(arguments.get_value("version").unwrap_or("0.0.0"));
+
+    let submodule_entries = input_paths
+        .iter()
+        .map(|item| read_object(Path::new(item)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let output = StaticLinker::new(&module_name, &module_version, &submodule_entries)
+        .image_type(image_type)
+        .strip(arguments.is_set("strip"))
+        .inline_functions(arguments.is_set("inline"))
+        .optimize_data_layout(arguments.is_set("optimize-data-layout"))
+        .remove_unused_functions(arguments.is_set("remove-unused"))
+        .export_filters(&arguments.get_values("export"))
+        .active_flags(&arguments.get_values("flag"))
+        .external_symbol_namespace(arguments.get_external_symbol_namespace())
+        .verify_external_functions(arguments.is_set("verify-external"))
+        .system_library_search_directories(&arguments.get_system_library_search_directories())
+        .warnings_as_errors(arguments.is_set("warnings-as-errors"))
+        .link()
+        .map_err(|error| error.to_string())?;
+
+    print_warnings(&output.warnings);
+
+    for function_name in &output.removed_function_names {
+        println!("removed: {}", function_name);
+    }
+
+    match image_type {
+        ImageType::Application => {
+            // the index is built by the subcommand "dynamic".
+            let image_index_entry = ImageIndexEntry {
+                function_index_list_entries: vec![],
+                entry_point_entries: vec![],
+                data_index_list_entries: vec![],
+                unified_external_library_entries: vec![],
+                unified_external_type_entries: vec![],
+                unified_external_function_entries: vec![],
+                external_function_index_entries: vec![],
+                dynamic_link_module_entries: vec![],
+            };
+
+            write_image(
+                Path::new(output_path),
+                &output.image_common_entry,
+                &image_index_entry,
+            )
+        }
+        _ => write_object(
+            Path::new(output_path),
+            &output.image_common_entry,
+            image_type == ImageType::SharedModule,
+        ),
+    }
+}
+
+fn run_dynamic(args: &[String]) -> Result<(), String> {
+    let arguments = Arguments::parse(
+        args,
+        &["module-dir", "flag", "system-library-dir"],
+        &[
+            "global-namespace",
+            "resolve-system-libraries",
+            "verify-external",
+            "warnings-as-errors",
+        ],
+    )?;
+
+    let [output_path, application_path] = arguments.positionals.as_slice() else {
+        return Err("Expect the output file and the application file.".to_owned());
+    };
+
+    let application_path = Path::new(application_path);
+    let module_directory = match arguments.get_value("module-dir") {
+        Some(directory) => PathBuf::from(directory),
+        // the parent of a bare file name is an empty path
+        None => match application_path.parent() {
+            Some(directory) if !directory.as_os_str().is_empty() => directory.to_path_buf(),
+            _ => PathBuf::from("."),
+        },
+    };
+
+    let mut system_library_search_directories = arguments.get_system_library_search_directories();
+    if arguments.is_set("resolve-system-libraries") {
+        system_library_search_directories.extend(get_default_library_search_directories());
+    }
+
+    let (application_entry, _) = read_image(application_path)?;
+    let module_provider = FileSystemModuleProvider::new(&module_directory);
+
+    let output = load_and_link_application(
+        &module_provider,
+        application_entry,
+        &arguments.get_values("flag"),
+        arguments.get_external_symbol_namespace(),
+        &system_library_search_directories,
+        &[],
+    )
+    .map_err(|error| error.to_string())?;
+
+    if arguments.is_set("verify-external") {
+        let verify_search_directories = if system_library_search_directories.is_empty() {
+            get_default_library_search_directories()
+        } else {
+            system_library_search_directories
+        };
+
+        verify_external_functions(
+            &output.image_index_entry.unified_external_library_entries,
+            &output.image_index_entry.unified_external_function_entries,
+            &verify_search_directories,
+        )
+        .map_err(|error| error.to_string())?;
+    }
+
+    if arguments.is_set("warnings-as-errors") {
+        if let Some(warning) = output.warnings.first() {
+            return Err(
+                LinkerError::new(LinkErrorType::WarningAsError(warning.clone())).to_string(),
+            );
+        }
+    }
+
+    print_warnings(&output.warnings);
+
+    write_image(
+        Path::new(output_path),
+        &output.image_common_entries[0],
+        &output.image_index_entry,
+    )
+}
+
+fn run_inspect(args: &[String]) -> Result<(), String> {
+    let arguments = Arguments::parse(args, &[], &["code"])?;
+
+    let [file_path] = arguments.positionals.as_slice() else {
+        return Err("Expect one image file.".to_owned());
+    };
+
+    let (image_common_entry, image_index_entry_opt) = read_any_image(Path::new(file_path))?;
+
+    let text = format_image(
+        &image_common_entry,
+        image_index_entry_opt.as_ref(),
+        arguments.is_set("code"),
+    )
+    .map_err(|message| format!("Invalid image file \"{}\": {}", file_path, message))?;
+
+    print!("{}", text);
+    Ok(())
+}
+
+/// Formats the content of the image as text, the image file may be
+/// corrupted, so the indices are checked.
+fn format_image(
+    image_common_entry: &ImageCommonEntry,
+    image_index_entry_opt: Option<&ImageIndexEntry>,
+    show_code: bool,
+) -> Result<String, String> {
+    let mut text = String::new();
+
+    writeln!(text, "name: {}", image_common_entry.name).unwrap();
+    writeln!(text, "version: {}", image_common_entry.version).unwrap();
+    writeln!(text, "type: {:?}", image_common_entry.image_type).unwrap();
+
+    writeln!(text, "import modules:").unwrap();
+    for import_module_entry in &image_common_entry.import_module_entries {
+        writeln!(
+            text,
+            "    {} {:?}",
+            import_module_entry.name, import_module_entry.module_dependency
+        )
+        .unwrap();
+    }
+
+    writeln!(text, "functions:").unwrap();
+    for (function_internal_index, export_function_entry) in image_common_entry
+        .export_function_entries
+        .iter()
+        .enumerate()
+    {
+        let function_entry = image_common_entry
+            .function_entries
+            .get(function_internal_index)
+            .ok_or_else(|| {
+                format!(
+                    "The function \"{}\" is not found.",
+                    export_function_entry.full_name
+                )
+            })?;
+        let type_entry = image_common_entry
+            .type_entries
+            .get(function_entry.type_index)
+            .ok_or_else(|| {
+                format!(
+                    "The type of function \"{}\" is not found.",
+                    export_function_entry.full_name
+                )
+            })?;
+
+        writeln!(
+            text,
+            "    {} {:?} {:?}",
+            export_function_entry.full_name, export_function_entry.visibility, type_entry
+        )
+        .unwrap();
+
+        if show_code {
+            for line in format_bytecode_as_text(&function_entry.code).lines() {
+                writeln!(text, "        {}", line).unwrap();
+            }
+        }
+    }
+
+    writeln!(text, "data:").unwrap();
+    for export_data_entry in &image_common_entry.export_data_entries {
+        writeln!(
+            text,
+            "    {} {:?} {:?}",
+            export_data_entry.full_name,
+            export_data_entry.visibility,
+            export_data_entry.section_type
+        )
+        .unwrap();
+    }
+
+    writeln!(text, "external libraries:").unwrap();
+    for external_library_entry in &image_common_entry.external_library_entries {
+        writeln!(
+            text,
+            "    {} {:?}",
+            external_library_entry.name, external_library_entry.value
+        )
+        .unwrap();
+    }
+
+    writeln!(text, "external functions:").unwrap();
+    for external_function_entry in &image_common_entry.external_function_entries {
+        let external_library_entry = image_common_entry
+            .external_library_entries
+            .get(external_function_entry.external_library_index)
+            .ok_or_else(|| {
+                format!(
+                    "The library of external function \"{}\" is not found.",
+                    external_function_entry.name
+                )
+            })?;
+
+        writeln!(
+            text,
+            "    {} (library: {})",
+            external_function_entry.name, external_library_entry.name
+        )
+        .unwrap();
+    }
+
+    // only the linked application has the index
+    if let Some(image_index_entry) =
+        image_index_entry_opt.filter(|item| !item.dynamic_link_module_entries.is_empty())
+    {
+        writeln!(text, "linked modules:").unwrap();
+        for dynamic_link_module_entry in &image_index_entry.dynamic_link_module_entries {
+            writeln!(
+                text,
+                "    {} ({})",
+                dynamic_link_module_entry.name,
+                format_module_location(&dynamic_link_module_entry.module_location)
+            )
+            .unwrap();
+        }
+
+        writeln!(text, "entry points:").unwrap();
+        for entry_point_entry in &image_index_entry.entry_point_entries {
+            writeln!(text, "    {}", entry_point_entry.unit_name).unwrap();
+        }
+    }
+
+    Ok(text)
+}
+
+fn print_warnings(warnings: &[LinkerWarning]) {
+    for warning in warnings {
+        eprintln!("warning: {}", warning);
+    }
+}
+
+fn get_file_stem(file_path: &Path) -> Result<String, String> {
+    file_path
+        .file_stem()
+        .map(|item| item.to_string_lossy().to_string())
+        .ok_or_else(|| {
+            format!(
+                "Cannot get the module name from \"{}\".",
+                file_path.display()
+            )
+        })
+}
+
+fn read_file(file_path: &Path) -> Result<Vec<u8>, String> {
+    std::fs::read(file_path)
+        .map_err(|error| format!("Failed to read file \"{}\": {}", file_path.display(), error))
+}
+
+fn read_image(file_path: &Path) -> Result<(ImageCommonEntry, ImageIndexEntry), String> {
+    read_image_file(&read_file(file_path)?).map_err(|error| {
+        format!(
+            "Failed to parse image file \"{}\": {}",
+            file_path.display(),
+            error
+        )
+    })
+}
+
+/// Reads an object file or a shared module, i.e. the image without the index.
+fn read_object(file_path: &Path) -> Result<ImageCommonEntry, String> {
+    read_object_file(&read_file(file_path)?).map_err(|error| {
+        format!(
+            "Failed to parse object file \"{}\": {}",
+            file_path.display(),
+            error
+        )
+    })
+}
+
+/// Reads an application (with the index), an object file or a shared module.
+fn read_any_image(file_path: &Path) -> Result<(ImageCommonEntry, Option<ImageIndexEntry>), String> {
+    let binary = read_file(file_path)?;
+
+    // only the applications carry the index sections
+    if let Ok((image_common_entry, image_index_entry)) = read_image_file(&binary) {
+        let image_index_entry_opt =
+            (image_common_entry.image_type == ImageType::Application).then_some(image_index_entry);
+        return Ok((image_common_entry, image_index_entry_opt));
+    }
+
+    read_object_file(&binary)
+        .map(|image_common_entry| (image_common_entry, None))
+        .map_err(|error| {
+            format!(
+                "Failed to parse image file \"{}\": {}",
+                file_path.display(),
+                error
+            )
+        })
+}
+
+fn write_image(
+    file_path: &Path,
+    image_common_entry: &ImageCommonEntry,
+    image_index_entry: &ImageIndexEntry,
+) -> Result<(), String> {
+    File::create(file_path)
+        .and_then(|file| {
+            let mut writer = BufWriter::new(file);
+            write_image_file(image_common_entry, image_index_entry, &mut writer)
+        })
+        .map_err(|error| {
+            format!(
+                "Failed to write file \"{}\": {}",
+                file_path.display(),
+                error
+            )
+        })
+}
+
+fn write_object(
+    file_path: &Path,
+    image_common_entry: &ImageCommonEntry,
+    generate_shared_module: bool,
+) -> Result<(), String> {
+    File::create(file_path)
+        .and_then(|file| {
+            let mut writer = BufWriter::new(file);
+            write_object_file(image_common_entry, generate_shared_module, &mut writer)
+        })
+        .map_err(|error| {
+            format!(
+                "Failed to write file \"{}\": {}",
+                file_path.display(),
+                error
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use anc_image::{
+        entry::{
+            ExportFunctionEntry, FunctionEntry, ImageCommonEntry, ImportModuleEntry,
+            LocalVariableListEntry, RelocateListEntry, TypeEntry,
+        },
+        module_image::{ImageType, Visibility},
+    };
+    use anc_isa::{EffectiveVersion, OperandDataType};
+
+    use super::{format_image, read_any_image, run_static, write_object, Arguments};
+
+    fn parse(args: &[&str]) -> Result<Arguments, String> {
+        let args = args.iter().map(|item| item.to_string()).collect::<Vec<_>>();
+        Arguments::parse(&args, &["name", "flag"], &["strip"])
+    }
+
+    #[test]
+    fn test_parse_arguments() {
+        let arguments = parse(&[
+            "out.anc", "--name", "hello", "a.anc", "--strip", "--flag", "x", "--flag", "y", "b.anc",
+        ])
+        .unwrap();
+
+        assert_eq!(arguments.positionals, vec!["out.anc", "a.anc", "b.anc"]);
+        assert_eq!(arguments.get_value("name"), Some("hello"));
+        assert_eq!(arguments.get_values("flag"), vec!["x", "y"]);
+        assert_eq!(arguments.get_value("flag"), Some("y"));
+        assert!(arguments.is_set("strip"));
+        assert!(!arguments.is_set("name-not-exists"));
+        assert_eq!(arguments.get_value("strip"), None);
+
+        // the value option at the end
+        assert_eq!(
+            parse(&["out.anc", "--name"]).err(),
+            Some("Missing the value of option \"--name\".".to_owned())
+        );
+
+        // unknown option
+        assert_eq!(
+            parse(&["out.anc", "--inline"]).err(),
+            Some("Unknown option \"--inline\".".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_static_link_and_inspect() {
+        let directory = std::env::temp_dir().join(format!(
+            "anc-link-static-link-and-inspect-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&directory).unwrap();
+
+        // fn get_number() -> i32 { imm_i32(42) }
+        let object_entry = ImageCommonEntry {
+            name: "hello".to_owned(),
+            version: EffectiveVersion::new(0, 0, 0),
+            image_type: ImageType::ObjectFile,
+            import_module_entries: vec![ImportModuleEntry::self_reference_entry()],
+            import_function_entries: vec![],
+            import_data_entries: vec![],
+            type_entries: vec![TypeEntry::new(vec![], vec![OperandDataType::I32])],
+            local_variable_list_entries: vec![LocalVariableListEntry::new(vec![])],
+            function_entries: vec![FunctionEntry::new(
+                0,
+                0,
+                vec![
+                    0x40, 0x01, 0x00, 0x00, 0x2a, 0x00, 0x00, 0x00, // imm_i32
+                    0xc0, 0x03, // end
+                ],
+            )],
+            read_only_data_entries: vec![],
+            read_write_data_entries: vec![],
+            uninit_data_entries: vec![],
+            export_function_entries: vec![ExportFunctionEntry::new(
+                "hello::get_number".to_owned(),
+                Visibility::Public,
+            )],
+            export_data_entries: vec![],
+            relocate_list_entries: vec![RelocateListEntry::new(vec![])],
+            external_library_entries: vec![],
+            external_function_entries: vec![],
+        };
+
+        let object_file_path = directory.join("hello.o");
+        let module_file_path = directory.join("hello.anc");
+        write_object(&object_file_path, &object_entry, false).unwrap();
+
+        let args = [&module_file_path, &object_file_path]
+            .iter()
+            .map(|item| item.to_string_lossy().to_string())
+            .chain(["--version".to_owned(), "1.2.3".to_owned()])
+            .collect::<Vec<_>>();
+        run_static(&args).unwrap();
+
+        // the shared module has no index
+        let (image_common_entry, image_index_entry_opt) =
+            read_any_image(Path::new(&module_file_path)).unwrap();
+        assert!(image_index_entry_opt.is_none());
+
+        let text = format_image(&image_common_entry, None, false).unwrap();
+        assert!(text.starts_with("name: hello\nversion: 1.2.3\ntype: SharedModule\n"));
+        assert!(text.contains("\n    hello::get_number Public "));
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
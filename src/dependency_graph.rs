@@ -0,0 +1,466 @@
+// Copyright (c) 2026 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+use anc_image::entry::{
+    DynamicLinkModuleEntry, ImageCommonEntry, ImportModuleEntry, ModuleLocation,
+};
+use anc_isa::ModuleDependency;
+use serde::Serialize;
+
+use crate::{
+    dynamic_linker::{
+        calculate_module_deepths, get_dynamic_link_module_name, resolve_import_module_index,
+    },
+    LinkErrorType, LinkerError,
+};
+
+/// The dependency graph of an application, the nodes are in the same
+/// order as the given modules, i.e. the first node is the application.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct DependencyGraph {
+    pub nodes: Vec<DependencyGraphNode>,
+    pub edges: Vec<DependencyGraphEdge>,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct DependencyGraphNode {
+    pub name: String,
+    pub version: String,
+
+    // the text of `ModuleLocation` (see `format_module_location`), it is `None`
+    // if the `DynamicLinkModuleEntry` of the module is not provided.
+    pub location: Option<String>,
+
+    // the max deepth, see `sort_modules_by_dependent_deepth`.
+    pub depth: usize,
+}
+
+/// The import relationship, i.e. module `from` imports module `to`.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct DependencyGraphEdge {
+    pub from: usize,
+    pub to: usize,
+
+    // the version declared by the importer, only for "Share" dependencies.
+    pub required_version: Option<String>,
+}
+
+/// Builds the dependency graph.
+///
+/// The modules can be unsorted, but the first module should be the application,
+/// and `dynamic_link_module_entries` can be empty if the locations are not required.
+pub fn build_dependency_graph(
+    image_common_entries: &[ImageCommonEntry],
+    dynamic_link_module_entries: &[DynamicLinkModuleEntry],
+) -> Result<DependencyGraph, LinkerError> {
+    let module_deepths = calculate_module_deepths(image_common_entries)?;
+
+    let nodes = image_common_entries
+        .iter()
+        .enumerate()
+        .map(|(module_index, module_entry)| {
+            let dynamic_link_module_name =
+                get_dynamic_link_module_name(image_common_entries, module_index);
+            let location = dynamic_link_module_entries
+                .iter()
+                .find(|item| item.name == dynamic_link_module_name)
+                .map(|item| format_module_location(&item.module_location).to_owned());
+
+            DependencyGraphNode {
+                name: module_entry.name.clone(),
+                version: module_entry.version.to_string(),
+                location,
+                depth: module_deepths[module_index],
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let self_reference_module = ImportModuleEntry::self_reference_entry();
+    let mut edges: Vec<DependencyGraphEdge> = vec![];
+
+    for (module_index, module_entry) in image_common_entries.iter().enumerate() {
+        for import_module_entry in &module_entry.import_module_entries {
+            // skip the self reference item
+            if import_module_entry == &self_reference_module {
+                continue;
+            }
+
+            // all edges are resolved since the depths are calculated.
+            let target_module_index =
+                resolve_import_module_index(image_common_entries, import_module_entry).ok_or_else(
+                    || {
+                        LinkerError::new(LinkErrorType::CannotLoadMoudle(
+                            import_module_entry.name.to_owned(),
+                            format!(
+                                "The module is required by \"{}\" but not provided.",
+                                module_entry.name
+                            ),
+                        ))
+                    },
+                )?;
+
+            let required_version = match import_module_entry.module_dependency.as_ref() {
+                ModuleDependency::Share(share) => Some(share.version.clone()),
+                _ => None,
+            };
+
+            edges.push(DependencyGraphEdge {
+                from: module_index,
+                to: target_module_index,
+                required_version,
+            });
+        }
+    }
+
+    Ok(DependencyGraph { nodes, edges })
+}
+
+/// One step of a dependency path, i.e. module `from` imports module `to`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DependencyPathStep {
+    // the name of the module, it is suffixed with the version when
+    // several versions of one module are linked, e.g. "json@2.0.0".
+    pub from: String,
+    pub to: String,
+
+    // the full names of the functions and data which create the edge.
+    pub imported_functions: Vec<String>,
+    pub imported_data: Vec<String>,
+}
+
+/// Finds every import path from the application module (i.e. the first module)
+/// to the specified module, to explain why the module is linked.
+///
+/// The `module_name` can be "name" or "name@version", the result is empty if
+/// the module is not found.
+pub fn explain_module_dependency(
+    image_common_entries: &[ImageCommonEntry],
+    module_name: &str,
+) -> Vec<Vec<DependencyPathStep>> {
+    let target_module_indices = (0..image_common_entries.len())
+        .filter(|module_index| {
+            image_common_entries[*module_index].name == module_name
+                || get_dynamic_link_module_name(image_common_entries, *module_index) == module_name
+        })
+        .collect::<Vec<_>>();
+
+    let mut paths: Vec<Vec<DependencyPathStep>> = vec![];
+    let mut current_path: Vec<DependencyPathStep> = vec![];
+    let mut current_module_indices: Vec<usize> = vec![0];
+
+    find_dependency_paths(
+        image_common_entries,
+        &target_module_indices,
+        &mut current_module_indices,
+        &mut current_path,
+        &mut paths,
+    );
+
+    paths
+}
+
+fn find_dependency_paths(
+    image_common_entries: &[ImageCommonEntry],
+    target_module_indices: &[usize],
+    current_module_indices: &mut Vec<usize>,
+    current_path: &mut Vec<DependencyPathStep>,
+    paths: &mut Vec<Vec<DependencyPathStep>>,
+) {
+    let module_index = *current_module_indices.last().unwrap();
+    let module_entry = &image_common_entries[module_index];
+
+    if target_module_indices.contains(&module_index) && !current_path.is_empty() {
+        paths.push(current_path.clone());
+        return;
+    }
+
+    let self_reference_module = ImportModuleEntry::self_reference_entry();
+
+    for (import_module_index, import_module_entry) in
+        module_entry.import_module_entries.iter().enumerate()
+    {
+        // skip the self reference item
+        if import_module_entry == &self_reference_module {
+            continue;
+        }
+
+        let Some(target_module_index) =
+            resolve_import_module_index(image_common_entries, import_module_entry)
+        else {
+            continue;
+        };
+
+        // avoid loops
+        if current_module_indices.contains(&target_module_index) {
+            continue;
+        }
+
+        let imported_functions = module_entry
+            .import_function_entries
+            .iter()
+            .filter(|item| item.import_module_index == import_module_index)
+            .map(|item| item.full_name.clone())
+            .collect::<Vec<_>>();
+
+        let imported_data = module_entry
+            .import_data_entries
+            .iter()
+            .filter(|item| item.import_module_index == import_module_index)
+            .map(|item| item.full_name.clone())
+            .collect::<Vec<_>>();
+
+        current_path.push(DependencyPathStep {
+            from: get_dynamic_link_module_name(image_common_entries, module_index),
+            to: get_dynamic_link_module_name(image_common_entries, target_module_index),
+            imported_functions,
+            imported_data,
+        });
+        current_module_indices.push(target_module_index);
+
+        find_dependency_paths(
+            image_common_entries,
+            target_module_indices,
+            current_module_indices,
+            current_path,
+            paths,
+        );
+
+        current_module_indices.pop();
+        current_path.pop();
+    }
+}
+
+impl DependencyGraph {
+    /// Renders the graph as Graphviz DOT.
+    pub fn to_dot(&self) -> String {
+        let escape = |text: &str| text.replace('\\', "\\\\").replace('"', "\\\"");
+
+        let mut lines: Vec<String> = vec!["digraph dependencies {".to_owned()];
+
+        for (node_index, node) in self.nodes.iter().enumerate() {
+            let mut label_parts = vec![
+                node.name.clone(),
+                node.version.clone(),
+                format!("depth: {}", node.depth),
+            ];
+            if let Some(location) = &node.location {
+                label_parts.push(location.clone());
+            }
+
+            let label = label_parts
+                .iter()
+                .map(|item| escape(item))
+                .collect::<Vec<_>>()
+                .join("\\n");
+
+            lines.push(format!("    node{} [label=\"{}\"];", node_index, label));
+        }
+
+        for edge in &self.edges {
+            match &edge.required_version {
+                Some(version) => lines.push(format!(
+                    "    node{} -> node{} [label=\"{}\"];",
+                    edge.from,
+                    edge.to,
+                    escape(version)
+                )),
+                None => lines.push(format!("    node{} -> node{};", edge.from, edge.to)),
+            }
+        }
+
+        lines.push("}".to_owned());
+        lines.join("\n")
+    }
+
+    /// Renders the graph as ASON.
+    pub fn to_ason(&self) -> Result<String, ason::AsonError> {
+        ason::to_string(self)
+    }
+}
+
+/// Gets the text of the module location, e.g. "embed" and "runtime".
+pub fn format_module_location(module_location: &ModuleLocation) -> &'static str {
+    match module_location {
+        ModuleLocation::Embed => "embed",
+        ModuleLocation::Runtime => "runtime",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anc_image::entry::{DynamicLinkModuleEntry, ModuleLocation};
+    use anc_isa::EffectiveVersion;
+    use pretty_assertions::assert_eq;
+
+    use crate::test_helpers::{
+        build_module, make_module_entry, make_runtime_dependency, make_share_dependency,
+    };
+
+    use super::{
+        build_dependency_graph, explain_module_dependency, DependencyGraphEdge, DependencyPathStep,
+    };
+
+    #[test]
+    fn test_build_dependency_graph() {
+        let modules = vec![
+            make_module_entry(
+                "app",
+                EffectiveVersion::new(1, 0, 0),
+                vec![
+                    make_runtime_dependency("std"),
+                    make_share_dependency("math", "1.0.0"),
+                ],
+            ),
+            make_module_entry("std", EffectiveVersion::new(1, 0, 0), vec![]),
+            make_module_entry(
+                "math",
+                EffectiveVersion::new(1, 0, 0),
+                vec![make_runtime_dependency("std")],
+            ),
+        ];
+
+        let dynamic_link_module_entries = vec![
+            DynamicLinkModuleEntry::new("app".to_owned(), Box::new(ModuleLocation::Embed)),
+            DynamicLinkModuleEntry::new("std".to_owned(), Box::new(ModuleLocation::Runtime)),
+        ];
+
+        let graph = build_dependency_graph(&modules, &dynamic_link_module_entries).unwrap();
+
+        assert_eq!(
+            graph
+                .nodes
+                .iter()
+                .map(|item| (item.name.as_str(), item.depth, item.location.is_some()))
+                .collect::<Vec<_>>(),
+            vec![("app", 0, true), ("std", 2, true), ("math", 1, false)]
+        );
+
+        assert_eq!(
+            graph.edges,
+            vec![
+                DependencyGraphEdge {
+                    from: 0,
+                    to: 1,
+                    required_version: None
+                },
+                DependencyGraphEdge {
+                    from: 0,
+                    to: 2,
+                    required_version: Some("1.0.0".to_owned())
+                },
+                DependencyGraphEdge {
+                    from: 2,
+                    to: 1,
+                    required_version: None
+                },
+            ]
+        );
+
+        assert_eq!(
+            graph.to_dot(),
+            r#"digraph dependencies {
+    node0 [label="app\n1.0.0\ndepth: 0\nembed"];
+    node1 [label="std\n1.0.0\ndepth: 2\nruntime"];
+    node2 [label="math\n1.0.0\ndepth: 1"];
+    node0 -> node1;
+    node0 -> node2 [label="1.0.0"];
+    node2 -> node1;
+}"#
+        );
+
+        // the whitespaces are ignored, the numbers are not checked
+        // because their text depends on the integer types.
+        let ason_text = graph
+            .to_ason()
+            .unwrap()
+            .chars()
+            .filter(|item| !item.is_whitespace())
+            .collect::<String>();
+        assert!(ason_text
+            .starts_with("{nodes:[{name:\"app\"version:\"1.0.0\"location:Option::Some(\"embed\")"));
+        assert!(ason_text.contains("{name:\"math\"version:\"1.0.0\"location:Option::None"));
+        assert!(ason_text.contains("edges:[{from:"));
+        assert!(ason_text.contains("required_version:Option::None"));
+        assert!(ason_text.contains("required_version:Option::Some(\"1.0.0\")"));
+    }
+
+    #[test]
+    fn test_explain_module_dependency() {
+        let modules = vec![
+            build_module(
+                "app",
+                r#"
+import fn std::add(i32,i32) -> i32
+import fn math::inc(i32) -> i32
+
+fn _start() -> i32 {
+    call(inc, call(add, imm_i32(0x11), imm_i32(0x13)))
+}
+"#,
+                &[
+                    make_runtime_dependency("std"),
+                    make_runtime_dependency("math"),
+                ],
+                EffectiveVersion::new(1, 0, 0),
+            ),
+            build_module(
+                "std",
+                r#"
+pub uninit data errno:i32
+
+pub fn add(left:i32, right:i32) -> i32 {
+    add_i32(
+        local_load_i32_s(left)
+        local_load_i32_s(right))
+}
+"#,
+                &[],
+                EffectiveVersion::new(1, 0, 0),
+            ),
+            build_module(
+                "math",
+                r#"
+import fn std::add(i32,i32) -> i32
+import uninit data std::errno type i32
+
+pub fn inc(num:i32) -> i32 {
+    call(add, local_load_i32_s(num), data_load_i32_s(errno))
+}
+"#,
+                &[make_runtime_dependency("std")],
+                EffectiveVersion::new(1, 0, 0),
+            ),
+        ];
+
+        assert_eq!(
+            explain_module_dependency(&modules, "std"),
+            vec![
+                vec![DependencyPathStep {
+                    from: "app".to_owned(),
+                    to: "std".to_owned(),
+                    imported_functions: vec!["std::add".to_owned()],
+                    imported_data: vec![],
+                }],
+                vec![
+                    DependencyPathStep {
+                        from: "app".to_owned(),
+                        to: "math".to_owned(),
+                        imported_functions: vec!["math::inc".to_owned()],
+                        imported_data: vec![],
+                    },
+                    DependencyPathStep {
+                        from: "math".to_owned(),
+                        to: "std".to_owned(),
+                        imported_functions: vec!["std::add".to_owned()],
+                        imported_data: vec!["std::errno".to_owned()],
+                    },
+                ],
+            ]
+        );
+
+        assert!(explain_module_dependency(&modules, "json").is_empty());
+    }
+}
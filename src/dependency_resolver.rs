@@ -0,0 +1,466 @@
+// Copyright (c) 2026 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+use std::path::{Component, Path, PathBuf};
+
+use anc_isa::{
+    DependencyCondition, EffectiveVersion, ExternalLibraryDependency, ModuleDependency,
+    VersionCompatibility,
+};
+
+use crate::{LinkErrorType, LinkerError};
+
+/// A version requirement of a "Share" dependency.
+#[derive(Debug, PartialEq, Clone)]
+pub struct VersionRequirement {
+    /// The name of the module (or submodule) which declares the dependency.
+    pub requester: String,
+
+    /// The required version, e.g. "1.2.3".
+    pub version: String,
+}
+
+impl VersionRequirement {
+    pub fn new(requester: &str, version: &str) -> Self {
+        Self {
+            requester: requester.to_owned(),
+            version: version.to_owned(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ResolvedVersion {
+    pub module_name: String,
+
+    /// The newest version among the compatible requirements.
+    pub version: String,
+
+    /// The requirements which are older than the resolved version, i.e.,
+    /// the dependency of these requesters is upgraded.
+    pub upgraded_requirements: Vec<VersionRequirement>,
+}
+
+/// Collects all version requirements of "Share" dependencies and
+/// picks one version for each module.
+///
+/// Unlike comparing versions pairwise while merging, the result does not
+/// depend on the order in which the requirements are added, and all
+/// requesters are reported when the requirements conflict.
+///
+/// e.g.
+///
+/// | requester  | module | version |
+/// |------------|--------|---------|
+/// | hello      | json   | 1.2.0   |
+/// | hello::foo | json   | 1.4.1   |
+/// | hello::bar | json   | 1.3.0   |
+///
+/// The resolved version of module "json" is "1.4.1", and the dependencies of
+/// "hello" and "hello::bar" are upgraded.
+///
+/// The requirements are grouped by compatibility, e.g. "1.2.0" and "1.4.1"
+/// are in the same group, while "2.0.0" is in another group. By default
+/// the requirements of a module should be in one group, when
+/// `side_by_side_versions` is enabled (i.e. the several major versions
+/// of a module are loaded side by side by the dynamic linker), each group
+/// is resolved to a version.
+#[derive(Debug, Default)]
+pub struct DependencyVersionResolver {
+    requirements_list: Vec<(/* module name */ String, Vec<VersionRequirement>)>,
+    side_by_side_versions: bool,
+}
+
+impl DependencyVersionResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn side_by_side_versions(mut self, side_by_side_versions: bool) -> Self {
+        self.side_by_side_versions = side_by_side_versions;
+        self
+    }
+
+    pub fn add_requirement(&mut self, module_name: &str, requester: &str, version: &str) {
+        let requirement = VersionRequirement::new(requester, version);
+
+        match self
+            .requirements_list
+            .iter_mut()
+            .find(|(name, _)| name == module_name)
+        {
+            Some((_, requirements)) => requirements.push(requirement),
+            None => self
+                .requirements_list
+                .push((module_name.to_owned(), vec![requirement])),
+        }
+    }
+
+    /// Returns the resolved versions in the order in which
+    /// the modules (and the groups of a module) were first required.
+    pub fn resolve(&self) -> Result<Vec<ResolvedVersion>, LinkerError> {
+        let mut resolved_versions = vec![];
+
+        for (module_name, requirements) in &self.requirements_list {
+            let conflict_error = || {
+                LinkerError::new(LinkErrorType::DependentVersionRequirementConflict(
+                    module_name.to_owned(),
+                    requirements.to_owned(),
+                ))
+            };
+
+            let requirement_groups = group_compatible_requirements(requirements);
+            if requirement_groups.len() > 1 && !self.side_by_side_versions {
+                return Err(conflict_error());
+            }
+
+            for requirement_group in requirement_groups {
+                // pick the newest version
+                let mut newest = requirement_group[0];
+                for requirement in &requirement_group[1..] {
+                    if EffectiveVersion::from_str(&requirement.version)
+                        .compatible(&EffectiveVersion::from_str(&newest.version))
+                        == VersionCompatibility::GreaterThan
+                    {
+                        newest = requirement;
+                    }
+                }
+
+                // all requirements should be compatible with the newest one
+                let newest_version = EffectiveVersion::from_str(&newest.version);
+                let mut upgraded_requirements = vec![];
+
+                for requirement in requirement_group {
+                    match EffectiveVersion::from_str(&requirement.version)
+                        .compatible(&newest_version)
+                    {
+                        VersionCompatibility::Equals => {
+                            // identical
+                        }
+                        VersionCompatibility::LessThan => {
+                            upgraded_requirements.push(requirement.to_owned());
+                        }
+                        VersionCompatibility::GreaterThan | VersionCompatibility::Conflict => {
+                            return Err(conflict_error());
+                        }
+                    }
+                }
+
+                resolved_versions.push(ResolvedVersion {
+                    module_name: module_name.to_owned(),
+                    version: newest.version.to_owned(),
+                    upgraded_requirements,
+                });
+            }
+        }
+
+        Ok(resolved_versions)
+    }
+}
+
+/// Groups the requirements which are compatible with each other,
+/// the groups are in the order in which they were first required.
+fn group_compatible_requirements(
+    requirements: &[VersionRequirement],
+) -> Vec<Vec<&VersionRequirement>> {
+    let mut requirement_groups: Vec<Vec<&VersionRequirement>> = vec![];
+
+    for requirement in requirements {
+        let version = EffectiveVersion::from_str(&requirement.version);
+
+        match requirement_groups.iter_mut().find(|group| {
+            version.compatible(&EffectiveVersion::from_str(&group[0].version))
+                != VersionCompatibility::Conflict
+        }) {
+            Some(group) => group.push(requirement),
+            None => requirement_groups.push(vec![requirement]),
+        }
+    }
+
+    requirement_groups
+}
+
+/// Evaluates the condition of a dependency with the active feature or
+/// platform flags, e.g. `is_true("feature_x")` is satisfied if
+/// "feature_x" is in the active flags.
+pub fn evaluate_dependency_condition(
+    condition: &DependencyCondition,
+    active_flags: &[&str],
+) -> bool {
+    match condition {
+        DependencyCondition::True => true,
+        DependencyCondition::False => false,
+        DependencyCondition::IsTrue(flag) => active_flags.contains(&flag.as_str()),
+        DependencyCondition::IsFalse(flag) => !active_flags.contains(&flag.as_str()),
+        DependencyCondition::EvalAnd(conditions) => conditions
+            .iter()
+            .all(|item| evaluate_dependency_condition(item, active_flags)),
+        DependencyCondition::EvalOr(conditions) => conditions
+            .iter()
+            .any(|item| evaluate_dependency_condition(item, active_flags)),
+        DependencyCondition::EvalNot(condition) => {
+            !evaluate_dependency_condition(condition, active_flags)
+        }
+    }
+}
+
+/// "Runtime" and "Module" dependencies have no condition, they are always active.
+pub fn is_module_dependency_active(
+    module_dependency: &ModuleDependency,
+    active_flags: &[&str],
+) -> bool {
+    match module_dependency {
+        ModuleDependency::Local(local) => {
+            evaluate_dependency_condition(&local.condition, active_flags)
+        }
+        ModuleDependency::Remote(remote) => {
+            evaluate_dependency_condition(&remote.condition, active_flags)
+        }
+        ModuleDependency::Share(share) => {
+            evaluate_dependency_condition(&share.condition, active_flags)
+        }
+        ModuleDependency::Runtime | ModuleDependency::Module => true,
+    }
+}
+
+/// "System" dependencies have no condition, they are always active.
+pub fn is_external_library_dependency_active(
+    external_library_dependency: &ExternalLibraryDependency,
+    active_flags: &[&str],
+) -> bool {
+    match external_library_dependency {
+        ExternalLibraryDependency::Local(local) => {
+            evaluate_dependency_condition(&local.condition, active_flags)
+        }
+        ExternalLibraryDependency::Remote(remote) => {
+            evaluate_dependency_condition(&remote.condition, active_flags)
+        }
+        ExternalLibraryDependency::Share(share) => {
+            evaluate_dependency_condition(&share.condition, active_flags)
+        }
+        ExternalLibraryDependency::System(_) => true,
+    }
+}
+
+/// Normalizes the path of a "Local" dependency against the base directory
+/// of the submodule which declares it, so that the paths which point to the
+/// same directory are identical, e.g.
+///
+/// | base directory | path          | normalized path |
+/// |----------------|---------------|-----------------|
+/// |                | ../lib        | ../lib          |
+/// | pkg/foo        | ../../../lib  | ../lib          |
+/// | pkg/foo        | ./lib         | pkg/foo/lib     |
+///
+/// The base directory should be relative to the output module, so does
+/// the normalized path.
+///
+/// Note that the path is normalized lexically (i.e., the file system
+/// is not accessed), because the dependency may not exist yet when linking.
+pub fn normalize_local_dependency_path(base_directory: &Path, path: &str) -> String {
+    let mut normalized = PathBuf::new();
+
+    for component in base_directory.join(path).components() {
+        match component {
+            Component::CurDir => {
+                // skip
+            }
+            Component::ParentDir => {
+                if matches!(
+                    normalized.components().next_back(),
+                    Some(Component::Normal(_))
+                ) {
+                    normalized.pop();
+                } else if !normalized.has_root() {
+                    normalized.push("..");
+                }
+            }
+            _ => normalized.push(component),
+        }
+    }
+
+    if normalized.as_os_str().is_empty() {
+        ".".to_owned()
+    } else {
+        normalized.to_string_lossy().into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use std::path::Path;
+
+    use anc_isa::DependencyCondition;
+
+    use crate::{
+        dependency_resolver::{
+            evaluate_dependency_condition, normalize_local_dependency_path,
+            DependencyVersionResolver, ResolvedVersion, VersionRequirement,
+        },
+        LinkErrorType, LinkerError,
+    };
+
+    #[test]
+    fn test_resolve_versions() {
+        let mut resolver = DependencyVersionResolver::new();
+        resolver.add_requirement("json", "hello", "1.2.0");
+        resolver.add_requirement("network", "hello", "2.0.1");
+        resolver.add_requirement("json", "hello::foo", "1.4.1");
+        resolver.add_requirement("json", "hello::bar", "1.3.0");
+        resolver.add_requirement("network", "hello::bar", "2.0.1");
+
+        assert_eq!(
+            resolver.resolve().unwrap(),
+            vec![
+                ResolvedVersion {
+                    module_name: "json".to_owned(),
+                    version: "1.4.1".to_owned(),
+                    upgraded_requirements: vec![
+                        VersionRequirement::new("hello", "1.2.0"),
+                        VersionRequirement::new("hello::bar", "1.3.0"),
+                    ]
+                },
+                ResolvedVersion {
+                    module_name: "network".to_owned(),
+                    version: "2.0.1".to_owned(),
+                    upgraded_requirements: vec![]
+                },
+            ]
+        );
+
+        // the result does not depend on the order of requirements
+        let mut resolver = DependencyVersionResolver::new();
+        resolver.add_requirement("json", "hello::foo", "1.4.1");
+        resolver.add_requirement("json", "hello::bar", "1.3.0");
+        resolver.add_requirement("json", "hello", "1.2.0");
+
+        assert_eq!(resolver.resolve().unwrap()[0].version, "1.4.1");
+    }
+
+    #[test]
+    fn test_resolve_versions_with_conflict() {
+        let mut resolver = DependencyVersionResolver::new();
+        resolver.add_requirement("json", "hello", "1.2.0");
+        resolver.add_requirement("json", "hello::foo", "2.0.0");
+        resolver.add_requirement("json", "hello::bar", "1.3.0");
+
+        assert!(matches!(
+            resolver.resolve(),
+            Err(LinkerError {
+                error_type: LinkErrorType::DependentVersionRequirementConflict(name, requirements)
+            }) if name == "json" && requirements == vec![
+                VersionRequirement::new("hello", "1.2.0"),
+                VersionRequirement::new("hello::foo", "2.0.0"),
+                VersionRequirement::new("hello::bar", "1.3.0"),
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_resolve_side_by_side_versions() {
+        let mut resolver = DependencyVersionResolver::new().side_by_side_versions(true);
+        resolver.add_requirement("json", "hello", "1.2.0");
+        resolver.add_requirement("json", "hello::foo", "2.0.0");
+        resolver.add_requirement("json", "hello::bar", "1.3.0");
+        resolver.add_requirement("json", "network", "2.1.0");
+
+        assert_eq!(
+            resolver.resolve().unwrap(),
+            vec![
+                ResolvedVersion {
+                    module_name: "json".to_owned(),
+                    version: "1.3.0".to_owned(),
+                    upgraded_requirements: vec![VersionRequirement::new("hello", "1.2.0")]
+                },
+                ResolvedVersion {
+                    module_name: "json".to_owned(),
+                    version: "2.1.0".to_owned(),
+                    upgraded_requirements: vec![VersionRequirement::new("hello::foo", "2.0.0")]
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_evaluate_dependency_condition() {
+        let active_flags = ["linux", "feature_gui"];
+
+        assert!(evaluate_dependency_condition(
+            &DependencyCondition::True,
+            &active_flags
+        ));
+        assert!(!evaluate_dependency_condition(
+            &DependencyCondition::False,
+            &active_flags
+        ));
+        assert!(evaluate_dependency_condition(
+            &DependencyCondition::IsTrue("linux".to_owned()),
+            &active_flags
+        ));
+        assert!(!evaluate_dependency_condition(
+            &DependencyCondition::IsTrue("windows".to_owned()),
+            &active_flags
+        ));
+        assert!(evaluate_dependency_condition(
+            &DependencyCondition::IsFalse("windows".to_owned()),
+            &active_flags
+        ));
+        assert!(evaluate_dependency_condition(
+            &DependencyCondition::EvalAnd(vec![
+                DependencyCondition::IsTrue("linux".to_owned()),
+                DependencyCondition::IsTrue("feature_gui".to_owned()),
+            ]),
+            &active_flags
+        ));
+        assert!(!evaluate_dependency_condition(
+            &DependencyCondition::EvalAnd(vec![
+                DependencyCondition::IsTrue("linux".to_owned()),
+                DependencyCondition::IsTrue("feature_tui".to_owned()),
+            ]),
+            &active_flags
+        ));
+        assert!(evaluate_dependency_condition(
+            &DependencyCondition::EvalOr(vec![
+                DependencyCondition::IsTrue("windows".to_owned()),
+                DependencyCondition::IsTrue("linux".to_owned()),
+            ]),
+            &active_flags
+        ));
+        assert!(evaluate_dependency_condition(
+            &DependencyCondition::EvalNot(Box::new(DependencyCondition::IsTrue(
+                "windows".to_owned()
+            ))),
+            &active_flags
+        ));
+    }
+
+    #[test]
+    fn test_normalize_local_dependency_path() {
+        assert_eq!(
+            normalize_local_dependency_path(Path::new(""), "../lib"),
+            "../lib"
+        );
+        assert_eq!(
+            normalize_local_dependency_path(Path::new("pkg/foo"), "../../../lib"),
+            "../lib"
+        );
+        assert_eq!(
+            normalize_local_dependency_path(Path::new("pkg/foo"), "./lib"),
+            "pkg/foo/lib"
+        );
+        assert_eq!(
+            normalize_local_dependency_path(Path::new("pkg"), "../pkg/./lib/.."),
+            "pkg"
+        );
+        assert_eq!(normalize_local_dependency_path(Path::new("pkg"), ".."), ".");
+        assert_eq!(
+            normalize_local_dependency_path(Path::new("pkg"), "/usr/lib/../share"),
+            "/usr/share"
+        );
+    }
+}
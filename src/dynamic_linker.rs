@@ -4,23 +4,35 @@
 // the Mozilla Public License version 2.0 and additional exceptions,
 // more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
 
-use std::collections::VecDeque;
+use std::{collections::VecDeque, path::PathBuf};
 
 use anc_image::{
     entry::{
         DataIndexEntry, DataIndexListEntry, DynamicLinkModuleEntry, EntryPointEntry,
         ExternalFunctionEntry, ExternalFunctionIndexEntry, ExternalFunctionIndexListEntry,
-        FunctionIndexEntry, FunctionIndexListEntry, ImageCommonEntry, ImageIndexEntry,
-        ImportModuleEntry, TypeEntry,
+        ExternalLibraryEntry, FunctionIndexEntry, FunctionIndexListEntry, ImageCommonEntry,
+        ImageIndexEntry, ImportModuleEntry, TypeEntry,
     },
-    module_image::Visibility,
+    module_image::{ImageType, Visibility},
+};
+use anc_isa::{
+    DataSectionType, EffectiveVersion, ModuleDependency, OperandDataType, VersionCompatibility,
 };
-use anc_isa::DataSectionType;
 use regex_anre::Regex;
 
 use crate::{
-    static_linker::{merge_external_library_entries, RemapIndices},
-    LinkErrorType, LinkerError, DEFAULT_ENTRY_FUNCTION_NAME,
+    dependency_resolver::is_module_dependency_active,
+    interface_fingerprint::{
+        build_interface_fingerprint_entries, check_interface_fingerprints,
+        check_module_interface_fingerprint, InterfaceFingerprintEntry,
+    },
+    static_linker::{
+        check_inactive_dependency_references, find_external_function, get_dependency_remap_index,
+        merge_external_library_entries, DroppableRemapIndices, RemapIndices,
+    },
+    system_library::resolve_system_library_entries,
+    ExternalSymbolNamespace, LinkErrorType, LinkerError, LinkerWarning,
+    DEFAULT_ENTRY_FUNCTION_NAME,
 };
 
 /// When an application is loaded, all its dependent modules must also be loaded.
@@ -62,6 +74,10 @@ use crate::{
 /// order:  (a) -> (b,c,e) -> (f) -> (d) -> (g,h) -> (i) -> (j)
 ///
 ///
+/// Several versions of one module (e.g. "json 1.x" and "json 2.x") can be
+/// loaded side by side, the import edges are bound to the module
+/// by `resolve_import_module_index`.
+///
 /// requires:
 /// - the first module should be the application itself.
 /// - all dependent modules should be resolved and have no conflict.
@@ -69,279 +85,305 @@ use crate::{
 pub fn sort_modules_by_dependent_deepth(
     image_common_entries: &mut [ImageCommonEntry],
 ) -> Result<(), LinkerError> {
-    // calculate the max deepth of each dependency
-    let mut dependency_with_deepth_items: Vec<(
-        /* module name */ String,
-        /* max deepth */ usize,
-    )> = vec![];
-
-    // push the current module.
-    // note that the name is the actual name of the module instead of "module",
-    // this is because the name is used for comparison.
-    dependency_with_deepth_items.push((
-        image_common_entries[0].name.clone(),
-        0, // the minimal number
-    ));
+    let module_deepths = calculate_module_deepths(image_common_entries)?;
+
+    // the module is identified by the name and version,
+    // because there may be several versions of one module.
+    let module_identities = image_common_entries
+        .iter()
+        .map(|item| (item.name.clone(), item.version))
+        .collect::<Vec<_>>();
+
+    // sort the modules by ascending (0->9)
+    image_common_entries.sort_by_key(|entry| {
+        let module_index = module_identities
+            .iter()
+            .position(|(name, version)| name == &entry.name && version == &entry.version)
+            .unwrap();
+        module_deepths[module_index]
+    });
+
+    Ok(())
+}
+
+/// Groups the modules by the max deepth (see `sort_modules_by_dependent_deepth`)
+/// for loading and verifying modules concurrently.
+///
+/// The groups are in the loading order, i.e. the deepest group first and the
+/// application module is the last group, so that no group depends on a later one.
+/// The modules in one group do not depend on each other, and they are
+/// ordered by name (and then version).
+///
+/// The result are the indices of the given modules.
+pub fn group_modules_by_dependent_deepth(
+    image_common_entries: &[ImageCommonEntry],
+) -> Result<Vec<Vec</* module index */ usize>>, LinkerError> {
+    let module_deepths = calculate_module_deepths(image_common_entries)?;
+    let max_deepth = module_deepths.iter().copied().max().unwrap_or(0);
+
+    let mut groups: Vec<Vec<usize>> = vec![vec![]; max_deepth + 1];
+    for (module_index, deepth) in module_deepths.iter().enumerate() {
+        groups[max_deepth - deepth].push(module_index);
+    }
+
+    for group in groups.iter_mut() {
+        group.sort_by(|left, right| {
+            let left_entry = &image_common_entries[*left];
+            let right_entry = &image_common_entries[*right];
+            // compare the version numbers directly, because the modules
+            // with the same name may have different major versions.
+            left_entry.name.cmp(&right_entry.name).then_with(|| {
+                let left_version = &left_entry.version;
+                let right_version = &right_entry.version;
+                (left_version.major, left_version.minor, left_version.patch).cmp(&(
+                    right_version.major,
+                    right_version.minor,
+                    right_version.patch,
+                ))
+            })
+        });
+    }
+
+    Ok(groups)
+}
+
+/// Calculates the max deepth of each module, see `sort_modules_by_dependent_deepth`
+/// for details.
+///
+/// The result is in the same order as the given modules.
+pub(crate) fn calculate_module_deepths(
+    image_common_entries: &[ImageCommonEntry],
+) -> Result<Vec</* max deepth */ usize>, LinkerError> {
+    calculate_active_module_deepths(image_common_entries, None)
+}
+
+/// Same as `calculate_module_deepths`, but the dependencies whose condition is
+/// not satisfied by the active flags are skipped, i.e. they are not required
+/// to be provided. `None` means that all dependencies are active.
+pub(crate) fn calculate_active_module_deepths(
+    image_common_entries: &[ImageCommonEntry],
+    active_flags_opt: Option<&[&str]>,
+) -> Result<Vec</* max deepth */ usize>, LinkerError> {
+    let mut module_deepths: Vec<Option<usize>> = vec![None; image_common_entries.len()];
+
+    // the first module, i.e., the application module itself.
+    module_deepths[0] = Some(0); // the minimal number
 
     let self_reference_module = ImportModuleEntry::self_reference_entry();
 
     // traversing through dependent tree,
     // finding the max-depth of each dependency.
     let mut pending_module_items: VecDeque<(
-        /* current module entry */ &ImageCommonEntry,
+        /* current module index */ usize,
         /* current module deepth */ usize,
     )> = VecDeque::new();
 
     // push the first module, i.e., the application module itself.
-    pending_module_items.push_back((&image_common_entries[0], 0));
+    pending_module_items.push_back((0, 0));
 
-    while !pending_module_items.is_empty() {
-        let (parent_module, parent_depth) = pending_module_items.pop_front().unwrap();
+    while let Some((parent_module_index, parent_depth)) = pending_module_items.pop_front() {
+        let parent_module = &image_common_entries[parent_module_index];
         let current_depth = parent_depth + 1;
 
         for dependency_new in &parent_module.import_module_entries {
-            // skip the self reference item
-            if dependency_new == &self_reference_module {
+            // skip the self reference item and the inactive dependencies
+            if dependency_new == &self_reference_module
+                || active_flags_opt.is_some_and(|active_flags| {
+                    !is_module_dependency_active(&dependency_new.module_dependency, active_flags)
+                })
+            {
                 continue;
             }
 
-            // find the existing item
-            let index_opt = dependency_with_deepth_items
-                .iter()
-                .position(|(name, _)| name == &dependency_new.name);
-
-            if let Some(index) = index_opt {
-                // record the max depth
-                if dependency_with_deepth_items[index].1 < current_depth {
-                    // update the depth
-                    dependency_with_deepth_items[index].1 = current_depth;
-                    // add to queue to re-calculate the depth of its subnodes.
-                    pending_module_items.push_back((
-                        image_common_entries
-                            .iter()
-                            .find(|item| item.name == dependency_new.name)
-                            .unwrap(),
-                        current_depth,
-                    ));
-                }
-            } else {
-                // add dependency item
-                dependency_with_deepth_items.push((dependency_new.name.to_owned(), current_depth));
-
-                // add to queue to calculate the depth of its subnodes,
+            let module_index = resolve_import_module_index(image_common_entries, dependency_new)
+                .ok_or_else(|| {
+                    LinkerError::new(LinkErrorType::CannotLoadMoudle(
+                        dependency_new.name.to_owned(),
+                        format!(
+                            "The module is required by \"{}\" but not provided.",
+                            parent_module.name
+                        ),
+                    ))
+                })?;
+
+            // record the max depth
+            if module_deepths[module_index].is_none_or(|deepth| deepth < current_depth) {
+                // update the depth
+                module_deepths[module_index] = Some(current_depth);
+
+                // add to queue to (re-)calculate the depth of its subnodes,
                 // i.e. subnodes of subnode.
-                pending_module_items.push_back((
-                    image_common_entries
-                        .iter()
-                        .find(|item| item.name == dependency_new.name)
-                        .unwrap(),
-                    current_depth,
-                ));
+                pending_module_items.push_back((module_index, current_depth));
             }
         }
     }
 
-    // sort the dependencies by ascending (0->9)
-    dependency_with_deepth_items.sort_by(|left, right| left.1.cmp(&right.1));
-
     // check the existance of dangling modules
-    if dependency_with_deepth_items
-        .iter()
-        .filter(|(_, deepth)| *deepth == 0)
-        .count()
-        > 1
-    {
+    if let Some(dangling_module_index) = module_deepths.iter().position(|item| item.is_none()) {
         return Err(LinkerError {
-            error_type: LinkErrorType::DanglingModule(dependency_with_deepth_items[1].0.clone()),
+            error_type: LinkErrorType::DanglingModule(
+                image_common_entries[dangling_module_index].name.clone(),
+            ),
         });
     }
 
-    // sort the modules
-    image_common_entries.sort_by(|left, right| {
-        let depth_left = dependency_with_deepth_items
-            .iter()
-            .find(|(name, _)| name == &left.name)
-            .unwrap()
-            .1;
-        let depth_right = dependency_with_deepth_items
-            .iter()
-            .find(|(name, _)| name == &right.name)
-            .unwrap()
-            .1;
-        depth_left.cmp(&depth_right)
-    });
-
-    Ok(())
+    Ok(module_deepths
+        .into_iter()
+        .map(|item| item.unwrap())
+        .collect())
 }
 
-pub fn dynamic_link(
-    // should be sorted entries
-    image_commmon_entries: &[ImageCommonEntry],
-    // can be unsorted, but the amount of 'dynamic_link_module_entries' should be
-    // the same as 'image_commmon_entries'
-    dynamic_link_module_entries: &[DynamicLinkModuleEntry],
-) -> Result<ImageIndexEntry, LinkerError> {
-    let mut function_index_list_entries: Vec<FunctionIndexListEntry> = vec![];
-    for (source_module_index, source_module_entry) in image_commmon_entries.iter().enumerate() {
-        let mut function_index_entries: Vec<FunctionIndexEntry> = vec![];
-
-        // add imported functon indices
-        for import_function_entry in source_module_entry.import_function_entries.iter() {
-            let target_module_name = &source_module_entry.import_module_entries
-                [import_function_entry.import_module_index]
-                .name;
-            let target_module_index = image_commmon_entries
-                .iter()
-                .position(|item| &item.name == target_module_name)
-                .unwrap();
-            let target_module = &image_commmon_entries[target_module_index];
+/// Finds the module which an import edge is bound to.
+///
+/// When there are several versions of the target module, the newest
+/// one which is compatible with the "Share" version declared by the importer
+/// is selected, e.g. "json 1.2.0" requires "json 1.4.1" instead of "json 2.0.0"
+/// when both of them are provided.
+pub fn resolve_import_module_index(
+    image_common_entries: &[ImageCommonEntry],
+    import_module_entry: &ImportModuleEntry,
+) -> Option<usize> {
+    let candidate_module_indices = image_common_entries
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| item.name == import_module_entry.name)
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
 
-            let expected_full_name = &import_function_entry.full_name;
-            let target_function_internal_index_opt = target_module
-                .export_function_entries
-                .iter()
-                .position(|item| &item.full_name == expected_full_name);
-
-            if let Some(target_function_internal_index) = target_function_internal_index_opt {
-                // check visibility
-                if target_module.export_function_entries[target_function_internal_index].visibility
-                    != Visibility::Public
-                {
-                    return Err(LinkerError::new(LinkErrorType::FunctionNotExported(
-                        expected_full_name.to_owned(),
-                    )));
-                }
+    if candidate_module_indices.len() <= 1 {
+        return candidate_module_indices.first().copied();
+    }
 
-                let target_function_entry =
-                    &target_module.function_entries[target_function_internal_index];
+    let ModuleDependency::Share(share) = import_module_entry.module_dependency.as_ref() else {
+        // only "Share" dependencies can be loaded side by side.
+        return candidate_module_indices.first().copied();
+    };
 
-                // check signature
-                let expected_type =
-                    &source_module_entry.type_entries[import_function_entry.type_index];
-                let actual_type = &target_module.type_entries[target_function_entry.type_index];
+    let required_version = EffectiveVersion::from_str(&share.version);
+    let mut selected_module_index_opt: Option<usize> = None;
 
-                if expected_type != actual_type {
-                    return Err(LinkerError::new(LinkErrorType::ImportFunctionTypeMismatch(
-                        expected_full_name.to_owned(),
-                    )));
-                }
+    for module_index in candidate_module_indices.iter().copied() {
+        let candidate_version = &image_common_entries[module_index].version;
+        if !matches!(
+            required_version.compatible(candidate_version),
+            VersionCompatibility::Equals | VersionCompatibility::LessThan
+        ) {
+            continue;
+        }
 
-                // add index item
-                function_index_entries.push(FunctionIndexEntry::new(
-                    target_module_index,
-                    target_function_internal_index,
-                ));
-            } else {
-                return Err(LinkerError::new(LinkErrorType::FunctionNotFound(
-                    expected_full_name.to_owned(),
-                )));
+        // pick the newest compatible version
+        let is_newer = match selected_module_index_opt {
+            Some(selected_module_index) => {
+                candidate_version.compatible(&image_common_entries[selected_module_index].version)
+                    == VersionCompatibility::GreaterThan
             }
-        }
+            None => true,
+        };
 
-        // add internal functon indices
-        for function_internal_index in 0..source_module_entry.function_entries.len() {
-            function_index_entries.push(FunctionIndexEntry::new(
-                source_module_index,
-                function_internal_index,
-            ));
+        if is_newer {
+            selected_module_index_opt = Some(module_index);
         }
-
-        // complete one module list
-        function_index_list_entries.push(FunctionIndexListEntry::new(function_index_entries));
     }
 
-    let mut data_index_list_entries: Vec<DataIndexListEntry> = vec![];
-    for (source_module_index, source_module_entry) in image_commmon_entries.iter().enumerate() {
-        let mut data_index_entries: Vec<DataIndexEntry> = vec![];
-
-        // add imported data indices
-        for import_data_entry in source_module_entry.import_data_entries.iter() {
-            let target_module_name = &source_module_entry.import_module_entries
-                [import_data_entry.import_module_index]
-                .name;
-            let target_module_index = image_commmon_entries
-                .iter()
-                .position(|item| &item.name == target_module_name)
-                .unwrap();
-            let target_module = &image_commmon_entries[target_module_index];
-
-            let expected_full_name = &import_data_entry.full_name;
-            let target_data_internal_index_opt = target_module
-                .export_data_entries
-                .iter()
-                .position(|item| &item.full_name == expected_full_name);
-
-            if let Some(target_data_internal_index) = target_data_internal_index_opt {
-                // check data section type
-                let target_export_data_entry =
-                    &target_module.export_data_entries[target_data_internal_index];
-
-                if target_export_data_entry.section_type != import_data_entry.data_section_type {
-                    return Err(LinkerError::new(LinkErrorType::ImportDataSectionMismatch(
-                        expected_full_name.to_owned(),
-                        import_data_entry.data_section_type,
-                    )));
-                }
-
-                // check visibility
-                if target_export_data_entry.visibility != Visibility::Public {
-                    return Err(LinkerError::new(LinkErrorType::DataNotExported(
-                        expected_full_name.to_owned(),
-                    )));
-                }
-
-                // check type
-                // let expected_type = ...;
-                // let actual_type = import_data_entry.memory_data_type;
-                // if expected_type != actual_type {
-                //     return Err(LinkerError::new(LinkErrorType::ImportDataTypeMismatch(
-                //         expected_full_name.to_owned(),
-                //     )));
-                // }
-
-                // add index item
-                data_index_entries.push(DataIndexEntry::new(
-                    target_module_index,
-                    target_data_internal_index,
-                    target_export_data_entry.section_type,
-                ));
-            } else {
-                return Err(LinkerError::new(LinkErrorType::DataNotFound(
-                    expected_full_name.to_owned(),
-                )));
-            }
-        }
-
-        // add internal data indices, .rodata
-        for data_internal_index in 0..source_module_entry.read_only_data_entries.len() {
-            data_index_entries.push(DataIndexEntry::new(
-                source_module_index,
-                data_internal_index,
-                DataSectionType::ReadOnly,
-            ));
-        }
+    // when there is no compatible version, the first candidate is returned
+    // and the mismatch is reported by `check_import_module_versions`.
+    selected_module_index_opt.or(candidate_module_indices.first().copied())
+}
 
-        // add internal data indices, .data
-        for data_internal_index in 0..source_module_entry.read_write_data_entries.len() {
-            data_index_entries.push(DataIndexEntry::new(
-                source_module_index,
-                data_internal_index,
-                DataSectionType::ReadWrite,
-            ));
-        }
+/// Gets the name of the `DynamicLinkModuleEntry` of a module.
+///
+/// When several versions of a module are linked side by side,
+/// the name is suffixed with the version to disambiguate them,
+/// e.g. "json@1.4.1" and "json@2.0.0", otherwise it is the module name.
+///
+/// `DynamicLinkModuleEntry` has no version field, so the suffix is part of
+/// the contract with the loader: a "name@version" entry refers to the module
+/// "name" of exactly that version (e.g. the file "json@1.4.1.anc", see
+/// `FileSystemModuleProvider`), use `split_dynamic_link_module_name` to
+/// get the two parts. A loader which does not understand the suffix can only
+/// load applications without side-by-side versions.
+pub fn get_dynamic_link_module_name(
+    image_common_entries: &[ImageCommonEntry],
+    module_index: usize,
+) -> String {
+    let module_entry = &image_common_entries[module_index];
+    let module_count = image_common_entries
+        .iter()
+        .filter(|item| item.name == module_entry.name)
+        .count();
 
-        // add internal data indices, .bss
-        for data_internal_index in 0..source_module_entry.uninit_data_entries.len() {
-            data_index_entries.push(DataIndexEntry::new(
-                source_module_index,
-                data_internal_index,
-                DataSectionType::Uninit,
-            ));
-        }
+    if module_count > 1 {
+        format!("{}@{}", module_entry.name, module_entry.version)
+    } else {
+        module_entry.name.to_owned()
+    }
+}
 
-        // complete one list
-        data_index_list_entries.push(DataIndexListEntry::new(data_index_entries));
+/// Splits the name of `DynamicLinkModuleEntry` into the module name and
+/// the version, see `get_dynamic_link_module_name`, e.g.
+///
+/// - "json@1.4.1" -> ("json", Some("1.4.1"))
+/// - "json" -> ("json", None)
+pub fn split_dynamic_link_module_name(dynamic_link_module_name: &str) -> (&str, Option<&str>) {
+    match dynamic_link_module_name.split_once('@') {
+        Some((module_name, version)) => (module_name, Some(version)),
+        None => (dynamic_link_module_name, None),
     }
+}
+
+/// Builds the index of the application.
+///
+/// The interface fingerprints of the dependent modules are returned next to
+/// the index, they should be recorded and passed to the next linking as
+/// `recorded_interface_fingerprint_entries`.
+///
+/// Note that the paths of "Local" external libraries are relative to the
+/// module which declares them, and the locations of the modules are unknown
+/// here, so the paths from different modules are compared as they are, i.e.
+/// the same library referenced by different relative paths is reported
+/// as `DependentSourceConflict`. Use "Share" or "System" libraries across
+/// modules instead.
+pub fn dynamic_link(
+    // should be sorted entries
+    image_commmon_entries: &[ImageCommonEntry],
+    // can be unsorted, but the amount of 'dynamic_link_module_entries' should be
+    // the same as 'image_commmon_entries'
+    dynamic_link_module_entries: &[DynamicLinkModuleEntry],
+    // The active feature or platform flags, used to evaluate
+    // the conditions of dependencies.
+    active_flags: &[&str],
+    // Determines whether two external functions are the same.
+    external_symbol_namespace: ExternalSymbolNamespace,
+    // The directories for resolving the "System" libraries to sonames (see
+    // `get_default_library_search_directories`), pass an empty slice to
+    // keep the "System" libraries unchanged.
+    system_library_search_directories: &[PathBuf],
+    // The interface fingerprints returned by the last linking,
+    // pass an empty slice to skip the check.
+    recorded_interface_fingerprint_entries: &[InterfaceFingerprintEntry],
+) -> Result<
+    (
+        ImageIndexEntry,
+        Vec<InterfaceFingerprintEntry>,
+        Vec<LinkerWarning>,
+    ),
+    LinkerError,
+> {
+    check_image_types(image_commmon_entries)?;
+    check_inactive_dependency_references(image_commmon_entries, active_flags)?;
+    check_import_module_versions(image_commmon_entries, active_flags)?;
+    check_interface_fingerprints(
+        image_commmon_entries,
+        recorded_interface_fingerprint_entries,
+    )?;
+
+    let function_index_list_entries = (0..image_commmon_entries.len())
+        .map(|module_index| build_function_index_list_entry(image_commmon_entries, module_index))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let data_index_list_entries = (0..image_commmon_entries.len())
+        .map(|module_index| build_data_index_list_entry(image_commmon_entries, module_index))
+        .collect::<Result<Vec<_>, _>>()?;
 
     // merge external library
     let external_library_entries_list = image_commmon_entries
@@ -349,7 +391,22 @@ pub fn dynamic_link(
         .map(|item| item.external_library_entries.as_slice())
         .collect::<Vec<_>>();
     let (external_library_entries, external_library_remap_indices_list) =
-        merge_external_library_entries(&external_library_entries_list)?;
+        merge_external_library_entries(
+            active_flags,
+            // the paths of "Local" libraries have been normalized by the static linker
+            // against each module itself, see `dynamic_link` for the limitation.
+            &[],
+            &external_library_entries_list,
+        )?;
+
+    let external_library_entries = if system_library_search_directories.is_empty() {
+        external_library_entries
+    } else {
+        resolve_system_library_entries(
+            &external_library_entries,
+            system_library_search_directories,
+        )?
+    };
 
     // merge external function and type entries
     let type_entries_list = image_commmon_entries
@@ -366,11 +423,14 @@ pub fn dynamic_link(
         type_entries_merged,
         external_function_entries_merged,
         external_function_remap_indices_list,
+        external_warnings,
     ) = build_external_function_and_type_entries(
+        external_symbol_namespace,
+        &external_library_entries_list,
         &external_library_remap_indices_list,
         &type_entries_list,
         &external_function_entries_list,
-    );
+    )?;
 
     let external_function_index_entries = external_function_remap_indices_list
         .iter()
@@ -383,18 +443,47 @@ pub fn dynamic_link(
         })
         .collect::<Vec<_>>();
 
-    let entry_point_entries = find_entry_points(&image_commmon_entries[0]);
+    let entry_point_entries = find_entry_points(&image_commmon_entries[0])?;
 
-    // sync the order of dynamic_link_module_entries to the one of image_commmon_entries
+    // sync the order of dynamic_link_module_entries to the one of image_commmon_entries.
+    // when there are several versions of one module, the entries are
+    // identified by "name@version", see `get_dynamic_link_module_name`.
     let mut sorted_dynamic_link_module_entries = vec![];
-    for image_commmon_entry in image_commmon_entries {
+    for module_index in 0..image_commmon_entries.len() {
+        let dynamic_link_module_name =
+            get_dynamic_link_module_name(image_commmon_entries, module_index);
         let dl_module = dynamic_link_module_entries
             .iter()
-            .find(|item| item.name == image_commmon_entry.name)
-            .unwrap();
+            .find(|item| item.name == dynamic_link_module_name)
+            .ok_or_else(|| {
+                LinkerError::new(LinkErrorType::CannotLoadMoudle(
+                    dynamic_link_module_name.clone(),
+                    "The location of the module is not provided.".to_owned(),
+                ))
+            })?;
         sorted_dynamic_link_module_entries.push(dl_module.to_owned());
     }
 
+    let mut warnings = (0..image_commmon_entries.len())
+        .flat_map(|module_index| {
+            find_module_dependency_warnings(image_commmon_entries, module_index, active_flags)
+        })
+        .collect::<Vec<_>>();
+    warnings.extend(external_warnings);
+
+    // the libraries which are declared by modules but no function is used.
+    warnings.extend(
+        external_library_entries
+            .iter()
+            .enumerate()
+            .filter(|(external_library_index, _)| {
+                !external_function_entries_merged
+                    .iter()
+                    .any(|item| item.external_library_index == *external_library_index)
+            })
+            .map(|(_, item)| LinkerWarning::UnusedExternalLibrary(item.name.to_owned())),
+    );
+
     let image_index_entry = ImageIndexEntry {
         function_index_list_entries,
         entry_point_entries,
@@ -406,130 +495,738 @@ pub fn dynamic_link(
         dynamic_link_module_entries: sorted_dynamic_link_module_entries,
     };
 
-    Ok(image_index_entry)
-}
+    let interface_fingerprint_entries = build_interface_fingerprint_entries(image_commmon_entries);
 
-fn build_external_function_and_type_entries(
-    external_library_remap_indices_list: &[RemapIndices],
-    type_entries_list: &[&[TypeEntry]],
-    external_function_entries_list: &[&[ExternalFunctionEntry]],
-) -> (
-    /* type_entries */ Vec<TypeEntry>,
-    /* external_function_entries */ Vec<ExternalFunctionEntry>,
-    /* external_function_remap_indices_list */ Vec<RemapIndices>,
-) {
-    let mut type_entries_merged: Vec<TypeEntry> = vec![];
-    let mut external_function_entries_merged: Vec<ExternalFunctionEntry> = vec![];
-    let mut external_function_remap_indices_list: Vec<RemapIndices> = vec![];
+    Ok((image_index_entry, interface_fingerprint_entries, warnings))
+}
 
-    for (submodule_index, external_function_entries) in
-        external_function_entries_list.iter().enumerate()
+/// Updates the index after a single module is replaced, instead of
+/// relinking all modules.
+///
+/// Only the index lists of the updated module and the modules which import
+/// it are rebuilt, the unified external tables are reused. An error is
+/// returned if the updated module breaks an existing binding, e.g. an
+/// imported function is removed or its signature is changed.
+///
+/// The updated module should have the same name (and position) as the
+/// old one, and use the same external functions and libraries, otherwise
+/// `RelinkModuleMismatch` is returned and a full `dynamic_link` is required.
+/// The `external_symbol_namespace` and `system_library_search_directories`
+/// should be the same as the ones used to build the previous index.
+///
+/// The interface fingerprint of the updated module is checked against
+/// the records, same as `dynamic_link`, and the fingerprints of the dependent
+/// modules and the warnings are returned next to the index, the warnings only
+/// cover the updated module and the modules which import it.
+#[allow(clippy::too_many_arguments)]
+pub fn dynamic_relink(
+    previous_image_index_entry: &ImageIndexEntry,
+    // should be sorted entries, and the updated module is placed
+    // at the position of the old one.
+    image_commmon_entries: &[ImageCommonEntry],
+    updated_module_index: usize,
+    updated_dynamic_link_module_entry: &DynamicLinkModuleEntry,
+    active_flags: &[&str],
+    external_symbol_namespace: ExternalSymbolNamespace,
+    system_library_search_directories: &[PathBuf],
+    recorded_interface_fingerprint_entries: &[InterfaceFingerprintEntry],
+) -> Result<
+    (
+        ImageIndexEntry,
+        Vec<InterfaceFingerprintEntry>,
+        Vec<LinkerWarning>,
+    ),
+    LinkerError,
+> {
+    let updated_module_name =
+        get_dynamic_link_module_name(image_commmon_entries, updated_module_index);
+
+    let previous_dynamic_link_module_entries =
+        &previous_image_index_entry.dynamic_link_module_entries;
+
+    // the modules which depend on the updated module may be
+    // placed before it if its dependencies changed.
+    let is_sorted = calculate_module_deepths(image_commmon_entries)?
+        .windows(2)
+        .all(|pair| pair[0] <= pair[1]);
+
+    if previous_dynamic_link_module_entries.len() != image_commmon_entries.len()
+        || previous_dynamic_link_module_entries[updated_module_index].name != updated_module_name
+        || updated_dynamic_link_module_entry.name != updated_module_name
+        || !is_sorted
     {
-        let mut indices = vec![];
-
-        for external_function_entry_source in external_function_entries.iter() {
-            let type_index_source = external_function_entry_source.type_index;
-            let type_entry_source = &type_entries_list[submodule_index][type_index_source];
-            let type_index_merged_opt = type_entries_merged
-                .iter()
-                .position(|item| item == type_entry_source);
-
-            let type_index_merged = match type_index_merged_opt {
-                Some(pos_merged) => {
-                    // found exists
-                    pos_merged
-                }
-                None => {
-                    // add entry
-                    let pos_new = type_entries_merged.len();
-                    type_entries_merged.push(type_entry_source.to_owned());
-                    pos_new
-                }
-            };
-
-            let external_library_index_merged = external_library_remap_indices_list
-                [submodule_index][external_function_entry_source.external_library_index];
-
-            // how to determine if two external functions are the same?
-            // Is it just checking the function name like in C/ELF programs,
-            // includes the library name?
-            let pos_merged_opt = external_function_entries_merged.iter().position(|item| {
-                item.name == external_function_entry_source.name
-                    && item.external_library_index == external_library_index_merged
-            });
+        return Err(LinkerError::new(LinkErrorType::RelinkModuleMismatch(
+            updated_module_name,
+        )));
+    }
 
-            match pos_merged_opt {
-                Some(pos_merged) => {
-                    // found exists
-                    // todo: check declare type
-                    indices.push(pos_merged);
-                }
-                None => {
-                    // add entry
-                    let pos_new = external_function_entries_merged.len();
+    check_image_types(image_commmon_entries)?;
+    check_inactive_dependency_references(
+        &image_commmon_entries[updated_module_index..=updated_module_index],
+        active_flags,
+    )?;
+    check_import_module_versions(image_commmon_entries, active_flags)?;
+    check_module_interface_fingerprint(
+        image_commmon_entries,
+        updated_module_index,
+        recorded_interface_fingerprint_entries,
+    )?;
+
+    let updated_module_entry = &image_commmon_entries[updated_module_index];
+
+    let mut function_index_list_entries = previous_image_index_entry
+        .function_index_list_entries
+        .clone();
+    let mut data_index_list_entries = previous_image_index_entry.data_index_list_entries.clone();
+    let mut warnings = vec![];
 
-                    let external_function_entry_merged = ExternalFunctionEntry::new(
-                        external_function_entry_source.name.clone(),
-                        external_library_index_merged,
-                        type_index_merged,
-                    );
-                    external_function_entries_merged.push(external_function_entry_merged);
-                    indices.push(pos_new);
-                }
-            }
+    for (source_module_index, source_module_entry) in image_commmon_entries.iter().enumerate() {
+        let is_affected = source_module_index == updated_module_index
+            || source_module_entry
+                .import_module_entries
+                .iter()
+                .any(|item| {
+                    resolve_import_module_index(image_commmon_entries, item)
+                        == Some(updated_module_index)
+                });
+
+        if is_affected {
+            function_index_list_entries[source_module_index] =
+                build_function_index_list_entry(image_commmon_entries, source_module_index)?;
+            data_index_list_entries[source_module_index] =
+                build_data_index_list_entry(image_commmon_entries, source_module_index)?;
+            warnings.extend(find_module_dependency_warnings(
+                image_commmon_entries,
+                source_module_index,
+                active_flags,
+            ));
         }
-
-        external_function_remap_indices_list.push(indices);
     }
 
-    (
+    // the "System" libraries of the previous index have been resolved,
+    // so the ones of the updated module are resolved before merging.
+    // note that the "System" libraries have no condition.
+    let updated_external_library_entries = if system_library_search_directories.is_empty() {
+        updated_module_entry.external_library_entries.clone()
+    } else {
+        resolve_system_library_entries(
+            &updated_module_entry.external_library_entries,
+            system_library_search_directories,
+        )?
+    };
+
+    // append the external libraries and functions of the updated module
+    // to the previous unified tables, the indices of the previous entries are
+    // unchanged because the previous entries are already unique.
+    let (external_library_entries, external_library_remap_indices_list) =
+        merge_external_library_entries(
+            active_flags,
+            &[],
+            &[
+                &previous_image_index_entry.unified_external_library_entries,
+                &updated_external_library_entries,
+            ],
+        )?;
+
+    let (
         type_entries_merged,
         external_function_entries_merged,
         external_function_remap_indices_list,
-    )
-}
+        external_warnings,
+    ) = build_external_function_and_type_entries(
+        external_symbol_namespace,
+        &[
+            &previous_image_index_entry.unified_external_library_entries,
+            &updated_external_library_entries,
+        ],
+        &external_library_remap_indices_list,
+        &[
+            &previous_image_index_entry.unified_external_type_entries,
+            &updated_module_entry.type_entries,
+        ],
+        &[
+            &previous_image_index_entry.unified_external_function_entries,
+            &updated_module_entry.external_function_entries,
+        ],
+    )?;
+    warnings.extend(external_warnings);
+
+    // the unified external tables are shared by all modules, the entries which
+    // are only used by the old module can not be removed without rebuilding the
+    // tables, so the updated module should use the same external functions
+    // and libraries as the old one.
+    let mut previous_external_function_indices = previous_image_index_entry
+        .external_function_index_entries[updated_module_index]
+        .index_entries
+        .iter()
+        .map(|item| item.unified_external_function_index)
+        .collect::<Vec<_>>();
+    previous_external_function_indices.sort();
+    previous_external_function_indices.dedup();
+
+    let mut updated_external_function_indices = external_function_remap_indices_list[1].clone();
+    updated_external_function_indices.sort();
+    updated_external_function_indices.dedup();
+
+    if updated_external_function_indices != previous_external_function_indices
+        || external_library_entries.len()
+            != previous_image_index_entry
+                .unified_external_library_entries
+                .len()
+        || type_entries_merged.len()
+            != previous_image_index_entry
+                .unified_external_type_entries
+                .len()
+    {
+        return Err(LinkerError::new(LinkErrorType::RelinkModuleMismatch(
+            updated_module_name,
+        )));
+    }
 
-/// Search the entry points:
-/// - 'app_module_name::_start' for the default entry point, entry point name is "_start".
-/// - 'app_module_name::app::{submodule_name}::_start' for the executable units, entry point name is the name of submodule.
-/// - 'app_module_name::tests::{submodule_name}::test_*' for unit tests, entry point name is "submodule_name::test_*".
-fn find_entry_points(main_module_entry: &ImageCommonEntry) -> Vec<EntryPointEntry> {
-    let mut entry_point_entries: Vec<EntryPointEntry> = vec![];
+    let mut external_function_index_entries = previous_image_index_entry
+        .external_function_index_entries
+        .clone();
+    external_function_index_entries[updated_module_index] = ExternalFunctionIndexListEntry::new(
+        external_function_remap_indices_list[1]
+            .iter()
+            .map(|index| ExternalFunctionIndexEntry::new(*index))
+            .collect(),
+    );
 
-    let export_function_entries = &main_module_entry.export_function_entries;
-    let import_functions_count = main_module_entry.import_function_entries.len();
-    let module_name = &main_module_entry.name;
+    // the libraries which are declared by the updated module but no function is used.
+    warnings.extend(
+        external_library_remap_indices_list[1]
+            .iter()
+            .enumerate()
+            .filter(|(external_library_index, merged_index)| {
+                merged_index.is_some()
+                    && !updated_module_entry
+                        .external_function_entries
+                        .iter()
+                        .any(|item| item.external_library_index == *external_library_index)
+            })
+            .map(|(external_library_index, _)| {
+                LinkerWarning::UnusedExternalLibrary(
+                    updated_external_library_entries[external_library_index]
+                        .name
+                        .to_owned(),
+                )
+            }),
+    );
 
-    // find the default entry
-    let default_entry_point_full_name = format!("{}::{}", module_name, DEFAULT_ENTRY_FUNCTION_NAME);
-    let default_entry_point_internal_index_opt = export_function_entries
-        .iter()
-        .position(|item| item.full_name == default_entry_point_full_name);
+    let entry_point_entries = if updated_module_index == 0 {
+        find_entry_points(updated_module_entry)?
+    } else {
+        previous_image_index_entry.entry_point_entries.clone()
+    };
 
-    if let Some(function_internal_index) = default_entry_point_internal_index_opt {
-        let function_public_index = import_functions_count + function_internal_index;
+    let mut dynamic_link_module_entries = previous_dynamic_link_module_entries.clone();
+    dynamic_link_module_entries[updated_module_index] = updated_dynamic_link_module_entry.clone();
 
-        entry_point_entries.push(EntryPointEntry::new(
-            DEFAULT_ENTRY_FUNCTION_NAME.to_owned(),
-            function_public_index,
-        ));
+    let image_index_entry = ImageIndexEntry {
+        function_index_list_entries,
+        entry_point_entries,
+        data_index_list_entries,
+        unified_external_library_entries: external_library_entries,
+        unified_external_type_entries: type_entries_merged,
+        unified_external_function_entries: external_function_entries_merged,
+        external_function_index_entries,
+        dynamic_link_module_entries,
     };
 
-    // find the executable units
-    let regex_bin = Regex::from_anre(&format!(
-        r#"
-        start, "{}::app::", (char_word+).name(unit_name), "::{}", end
-        "#,
-        module_name, DEFAULT_ENTRY_FUNCTION_NAME
-    ))
-    .unwrap();
+    let interface_fingerprint_entries = build_interface_fingerprint_entries(image_commmon_entries);
 
-    for (function_internal_index, function_full_name) in export_function_entries
-        .iter()
-        .map(|item| &item.full_name)
-        .enumerate()
-    {
+    Ok((image_index_entry, interface_fingerprint_entries, warnings))
+}
+
+/// Builds the function index list of the specified module, i.e. maps
+/// the function public indices of the module to (module index, internal index).
+fn build_function_index_list_entry(
+    image_commmon_entries: &[ImageCommonEntry],
+    source_module_index: usize,
+) -> Result<FunctionIndexListEntry, LinkerError> {
+    let source_module_entry = &image_commmon_entries[source_module_index];
+    let mut function_index_entries: Vec<FunctionIndexEntry> = vec![];
+
+    // add imported functon indices
+    for import_function_entry in source_module_entry.import_function_entries.iter() {
+        let target_module_index = resolve_import_module_index(
+            image_commmon_entries,
+            &source_module_entry.import_module_entries[import_function_entry.import_module_index],
+        )
+        .unwrap();
+        let target_module = &image_commmon_entries[target_module_index];
+
+        let expected_full_name = &import_function_entry.full_name;
+        let target_function_internal_index_opt = target_module
+            .export_function_entries
+            .iter()
+            .position(|item| &item.full_name == expected_full_name);
+
+        if let Some(target_function_internal_index) = target_function_internal_index_opt {
+            // check visibility
+            if target_module.export_function_entries[target_function_internal_index].visibility
+                != Visibility::Public
+            {
+                return Err(LinkerError::new(LinkErrorType::FunctionNotExported(
+                    expected_full_name.to_owned(),
+                )));
+            }
+
+            let target_function_entry =
+                &target_module.function_entries[target_function_internal_index];
+
+            // check signature
+            let expected_type = &source_module_entry.type_entries[import_function_entry.type_index];
+            let actual_type = &target_module.type_entries[target_function_entry.type_index];
+
+            if expected_type != actual_type {
+                return Err(LinkerError::new(LinkErrorType::ImportFunctionTypeMismatch(
+                    expected_full_name.to_owned(),
+                )));
+            }
+
+            // add index item
+            function_index_entries.push(FunctionIndexEntry::new(
+                target_module_index,
+                target_function_internal_index,
+            ));
+        } else {
+            return Err(LinkerError::new(LinkErrorType::FunctionNotFound(
+                expected_full_name.to_owned(),
+            )));
+        }
+    }
+
+    // add internal functon indices
+    for function_internal_index in 0..source_module_entry.function_entries.len() {
+        function_index_entries.push(FunctionIndexEntry::new(
+            source_module_index,
+            function_internal_index,
+        ));
+    }
+
+    Ok(FunctionIndexListEntry::new(function_index_entries))
+}
+
+/// Builds the data index list of the specified module, i.e. maps
+/// the data public indices of the module to (module index, internal index, section).
+fn build_data_index_list_entry(
+    image_commmon_entries: &[ImageCommonEntry],
+    source_module_index: usize,
+) -> Result<DataIndexListEntry, LinkerError> {
+    let source_module_entry = &image_commmon_entries[source_module_index];
+    let mut data_index_entries: Vec<DataIndexEntry> = vec![];
+
+    // add imported data indices
+    for import_data_entry in source_module_entry.import_data_entries.iter() {
+        let target_module_index = resolve_import_module_index(
+            image_commmon_entries,
+            &source_module_entry.import_module_entries[import_data_entry.import_module_index],
+        )
+        .unwrap();
+        let target_module = &image_commmon_entries[target_module_index];
+
+        let expected_full_name = &import_data_entry.full_name;
+        let target_data_internal_index_opt = target_module
+            .export_data_entries
+            .iter()
+            .position(|item| &item.full_name == expected_full_name);
+
+        if let Some(target_data_internal_index) = target_data_internal_index_opt {
+            // check data section type
+            let target_export_data_entry =
+                &target_module.export_data_entries[target_data_internal_index];
+
+            if target_export_data_entry.section_type != import_data_entry.data_section_type {
+                return Err(LinkerError::new(LinkErrorType::ImportDataSectionMismatch(
+                    expected_full_name.to_owned(),
+                    import_data_entry.data_section_type,
+                )));
+            }
+
+            // check visibility
+            if target_export_data_entry.visibility != Visibility::Public {
+                return Err(LinkerError::new(LinkErrorType::DataNotExported(
+                    expected_full_name.to_owned(),
+                )));
+            }
+
+            // check type
+            // let expected_type = ...;
+            // let actual_type = import_data_entry.memory_data_type;
+            // if expected_type != actual_type {
+            //     return Err(LinkerError::new(LinkErrorType::ImportDataTypeMismatch(
+            //         expected_full_name.to_owned(),
+            //     )));
+            // }
+
+            // add index item
+            data_index_entries.push(DataIndexEntry::new(
+                target_module_index,
+                target_data_internal_index,
+                target_export_data_entry.section_type,
+            ));
+        } else {
+            return Err(LinkerError::new(LinkErrorType::DataNotFound(
+                expected_full_name.to_owned(),
+            )));
+        }
+    }
+
+    // add internal data indices, .rodata
+    for data_internal_index in 0..source_module_entry.read_only_data_entries.len() {
+        data_index_entries.push(DataIndexEntry::new(
+            source_module_index,
+            data_internal_index,
+            DataSectionType::ReadOnly,
+        ));
+    }
+
+    // add internal data indices, .data
+    for data_internal_index in 0..source_module_entry.read_write_data_entries.len() {
+        data_index_entries.push(DataIndexEntry::new(
+            source_module_index,
+            data_internal_index,
+            DataSectionType::ReadWrite,
+        ));
+    }
+
+    // add internal data indices, .bss
+    for data_internal_index in 0..source_module_entry.uninit_data_entries.len() {
+        data_index_entries.push(DataIndexEntry::new(
+            source_module_index,
+            data_internal_index,
+            DataSectionType::Uninit,
+        ));
+    }
+
+    Ok(DataIndexListEntry::new(data_index_entries))
+}
+
+/// Checks the image type of each module:
+///
+/// - the first module should be an application or a shared module.
+/// - the dependent modules should be shared modules.
+///
+/// Object files are not allowed since they may have unresolved
+/// internal references.
+fn check_image_types(image_commmon_entries: &[ImageCommonEntry]) -> Result<(), LinkerError> {
+    for (module_index, module_entry) in image_commmon_entries.iter().enumerate() {
+        let is_allowed = match module_entry.image_type {
+            ImageType::Application => module_index == 0,
+            ImageType::SharedModule => true,
+            ImageType::ObjectFile => false,
+        };
+
+        if !is_allowed {
+            return Err(LinkerError::new(LinkErrorType::UnexpectedImageType(
+                module_entry.name.to_owned(),
+                module_entry.image_type,
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that the version of each dependent module satisfies the
+/// "Share" version declared by the importer, e.g. a module which requires
+/// "std 1.2.0" can be linked with "std 1.2.0" and "std 1.3.1", but not
+/// with "std 1.1.0" and "std 2.0.0".
+fn check_import_module_versions(
+    image_commmon_entries: &[ImageCommonEntry],
+    active_flags: &[&str],
+) -> Result<(), LinkerError> {
+    for (source_module_index, source_module_entry) in image_commmon_entries.iter().enumerate() {
+        for import_module_entry in &source_module_entry.import_module_entries {
+            // the dependencies whose condition is not satisfied are ignored.
+            if !is_module_dependency_active(&import_module_entry.module_dependency, active_flags) {
+                continue;
+            }
+
+            // only "Share" dependencies have version
+            let ModuleDependency::Share(share) = import_module_entry.module_dependency.as_ref()
+            else {
+                continue;
+            };
+
+            let target_module =
+                resolve_import_module_index(image_commmon_entries, import_module_entry)
+                    .map(|module_index| &image_commmon_entries[module_index])
+                    .ok_or_else(|| {
+                        LinkerError::new(LinkErrorType::CannotLoadMoudle(
+                            import_module_entry.name.to_owned(),
+                            format!(
+                                "The module is required by \"{}\" but not provided.",
+                                source_module_entry.name
+                            ),
+                        ))
+                    })?;
+
+            match EffectiveVersion::from_str(&share.version).compatible(&target_module.version) {
+                VersionCompatibility::Equals | VersionCompatibility::LessThan => {
+                    // the provided version is equal to or newer than the required one.
+                }
+                VersionCompatibility::GreaterThan | VersionCompatibility::Conflict => {
+                    return Err(LinkerError::new(LinkErrorType::ModuleVersionMismatch(
+                        import_module_entry.name.to_owned(),
+                        get_dynamic_link_module_name(image_commmon_entries, source_module_index),
+                        share.version.to_owned(),
+                        target_module.version,
+                    )));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Finds the "Share" dependencies of the specified module which are linked
+/// to newer versions, and the dependent modules which nothing is imported from.
+///
+/// The versions should have been checked by `check_import_module_versions`.
+fn find_module_dependency_warnings(
+    image_commmon_entries: &[ImageCommonEntry],
+    source_module_index: usize,
+    active_flags: &[&str],
+) -> Vec<LinkerWarning> {
+    let source_module_entry = &image_commmon_entries[source_module_index];
+    let self_reference_module = ImportModuleEntry::self_reference_entry();
+    let mut warnings = vec![];
+
+    for (import_module_index, import_module_entry) in
+        source_module_entry.import_module_entries.iter().enumerate()
+    {
+        // the dependencies whose condition is not satisfied are ignored.
+        if import_module_entry == &self_reference_module
+            || !is_module_dependency_active(&import_module_entry.module_dependency, active_flags)
+        {
+            continue;
+        }
+
+        if let ModuleDependency::Share(share) = import_module_entry.module_dependency.as_ref() {
+            let target_module_index =
+                resolve_import_module_index(image_commmon_entries, import_module_entry).unwrap();
+            let target_module = &image_commmon_entries[target_module_index];
+
+            if EffectiveVersion::from_str(&share.version).compatible(&target_module.version)
+                == VersionCompatibility::LessThan
+            {
+                warnings.push(LinkerWarning::DependencyUpgraded(
+                    import_module_entry.name.to_owned(),
+                    source_module_entry.name.to_owned(),
+                    share.version.to_owned(),
+                    target_module.version.to_string(),
+                ));
+            }
+        }
+
+        let is_used = source_module_entry
+            .import_function_entries
+            .iter()
+            .any(|item| item.import_module_index == import_module_index)
+            || source_module_entry
+                .import_data_entries
+                .iter()
+                .any(|item| item.import_module_index == import_module_index);
+
+        if !is_used {
+            warnings.push(LinkerWarning::UnusedImportModule(
+                import_module_entry.name.to_owned(),
+            ));
+        }
+    }
+
+    warnings
+}
+
+#[allow(clippy::type_complexity)]
+fn build_external_function_and_type_entries(
+    external_symbol_namespace: ExternalSymbolNamespace,
+    external_library_entries_list: &[&[ExternalLibraryEntry]],
+    external_library_remap_indices_list: &[DroppableRemapIndices],
+    type_entries_list: &[&[TypeEntry]],
+    external_function_entries_list: &[&[ExternalFunctionEntry]],
+) -> Result<
+    (
+        /* type_entries */ Vec<TypeEntry>,
+        /* external_function_entries */ Vec<ExternalFunctionEntry>,
+        /* external_function_remap_indices_list */ Vec<RemapIndices>,
+        /* warnings */ Vec<LinkerWarning>,
+    ),
+    LinkerError,
+> {
+    let mut type_entries_merged: Vec<TypeEntry> = vec![];
+    let mut external_function_entries_merged: Vec<ExternalFunctionEntry> = vec![];
+    let mut external_function_remap_indices_list: Vec<RemapIndices> = vec![];
+    let mut warnings: Vec<LinkerWarning> = vec![];
+
+    // the library names of the merged entries, for the warnings.
+    let mut library_names_merged: Vec<&str> = vec![];
+
+    for (submodule_index, external_function_entries) in
+        external_function_entries_list.iter().enumerate()
+    {
+        let mut indices = vec![];
+
+        for external_function_entry_source in external_function_entries.iter() {
+            let type_index_source = external_function_entry_source.type_index;
+            let type_entry_source = &type_entries_list[submodule_index][type_index_source];
+            let type_index_merged_opt = type_entries_merged
+                .iter()
+                .position(|item| item == type_entry_source);
+
+            let type_index_merged = match type_index_merged_opt {
+                Some(pos_merged) => {
+                    // found exists
+                    pos_merged
+                }
+                None => {
+                    // add entry
+                    let pos_new = type_entries_merged.len();
+                    type_entries_merged.push(type_entry_source.to_owned());
+                    pos_new
+                }
+            };
+
+            let external_library_index = external_function_entry_source.external_library_index;
+            let library_name =
+                &external_library_entries_list[submodule_index][external_library_index].name;
+            let external_library_index_merged = get_dependency_remap_index(
+                &external_library_remap_indices_list[submodule_index],
+                external_library_index,
+                library_name,
+                &external_function_entry_source.name,
+            )?;
+
+            let pos_merged_opt = find_external_function(
+                external_symbol_namespace,
+                &external_function_entries_merged,
+                &external_function_entry_source.name,
+                external_library_index_merged,
+            );
+
+            match pos_merged_opt {
+                Some(pos_merged) => {
+                    // found exists
+                    // todo: check declare type in the library-qualified namespace
+                    if external_symbol_namespace == ExternalSymbolNamespace::Global
+                        && external_function_entries_merged[pos_merged].type_index
+                            != type_index_merged
+                    {
+                        return Err(LinkerError::new(LinkErrorType::ExternalFunctionConflict(
+                            external_function_entry_source.name.to_owned(),
+                        )));
+                    }
+
+                    if external_function_entries_merged[pos_merged].external_library_index
+                        != external_library_index_merged
+                    {
+                        let warning = LinkerWarning::ExternalFunctionMerged(
+                            external_function_entry_source.name.to_owned(),
+                            library_names_merged[pos_merged].to_owned(),
+                            library_name.to_owned(),
+                        );
+                        if !warnings.contains(&warning) {
+                            warnings.push(warning);
+                        }
+                    }
+
+                    indices.push(pos_merged);
+                }
+                None => {
+                    // add entry
+                    let pos_new = external_function_entries_merged.len();
+
+                    let external_function_entry_merged = ExternalFunctionEntry::new(
+                        external_function_entry_source.name.clone(),
+                        external_library_index_merged,
+                        type_index_merged,
+                    );
+                    external_function_entries_merged.push(external_function_entry_merged);
+                    library_names_merged.push(library_name);
+                    indices.push(pos_new);
+                }
+            }
+        }
+
+        external_function_remap_indices_list.push(indices);
+    }
+
+    Ok((
+        type_entries_merged,
+        external_function_entries_merged,
+        external_function_remap_indices_list,
+        warnings,
+    ))
+}
+
+/// Search the entry points:
+/// - 'app_module_name::_start' for the default entry point, entry point name is "_start".
+/// - 'app_module_name::app::{submodule_name}::_start' for the executable units, entry point name is the name of submodule.
+/// - 'app_module_name::tests::{submodule_name}::test_*' for unit tests, entry point name is "submodule_name::test_*".
+///
+/// All entry point functions should have no parameters and return at most one "i32",
+/// otherwise an error is returned.
+fn find_entry_points(
+    main_module_entry: &ImageCommonEntry,
+) -> Result<Vec<EntryPointEntry>, LinkerError> {
+    let mut entry_point_entries: Vec<EntryPointEntry> = vec![];
+
+    let export_function_entries = &main_module_entry.export_function_entries;
+    let import_functions_count = main_module_entry.import_function_entries.len();
+    let module_name = &main_module_entry.name;
+
+    // find the default entry
+    let default_entry_point_full_name = format!("{}::{}", module_name, DEFAULT_ENTRY_FUNCTION_NAME);
+    let default_entry_point_internal_index_opt = export_function_entries
+        .iter()
+        .position(|item| item.full_name == default_entry_point_full_name);
+
+    if let Some(function_internal_index) = default_entry_point_internal_index_opt {
+        check_entry_point_type(main_module_entry, function_internal_index).map_err(
+            |actual_type| {
+                LinkerError::new(LinkErrorType::EntryPointTypeMismatch(
+                    default_entry_point_full_name.clone(),
+                    actual_type,
+                ))
+            },
+        )?;
+
+        let function_public_index = import_functions_count + function_internal_index;
+
+        entry_point_entries.push(EntryPointEntry::new(
+            DEFAULT_ENTRY_FUNCTION_NAME.to_owned(),
+            function_public_index,
+        ));
+    };
+
+    // find the executable units
+    let regex_bin = Regex::from_anre(&format!(
+        r#"
+        start, "{}::app::", (char_word+).name(unit_name), "::{}", end
+        "#,
+        module_name, DEFAULT_ENTRY_FUNCTION_NAME
+    ))
+    .unwrap();
+
+    for (function_internal_index, function_full_name) in export_function_entries
+        .iter()
+        .map(|item| &item.full_name)
+        .enumerate()
+    {
         if let Some(caps) = regex_bin.captures(function_full_name) {
+            check_entry_point_type(main_module_entry, function_internal_index).map_err(
+                |actual_type| {
+                    LinkerError::new(LinkErrorType::EntryPointTypeMismatch(
+                        function_full_name.to_owned(),
+                        actual_type,
+                    ))
+                },
+            )?;
+
             let unit_name = caps.name("unit_name").unwrap().as_str();
             let function_public_index = import_functions_count + function_internal_index;
             entry_point_entries.push(EntryPointEntry::new(
@@ -554,6 +1251,15 @@ fn find_entry_points(main_module_entry: &ImageCommonEntry) -> Vec<EntryPointEntr
         .enumerate()
     {
         if let Some(caps) = regex_bin.captures(function_full_name) {
+            check_entry_point_type(main_module_entry, function_internal_index).map_err(
+                |actual_type| {
+                    LinkerError::new(LinkErrorType::UnitTestTypeMismatch(
+                        function_full_name.to_owned(),
+                        actual_type,
+                    ))
+                },
+            )?;
+
             let unit_name = caps.name("unit_name").unwrap().as_str();
             let function_public_index = import_functions_count + function_internal_index;
             entry_point_entries.push(EntryPointEntry::new(
@@ -563,12 +1269,29 @@ fn find_entry_points(main_module_entry: &ImageCommonEntry) -> Vec<EntryPointEntr
         }
     }
 
-    entry_point_entries
+    Ok(entry_point_entries)
+}
+
+/// Check that the signature of the entry point function is
+/// "() -> ()" or "() -> i32", returns the actual type if it is not.
+fn check_entry_point_type(
+    main_module_entry: &ImageCommonEntry,
+    function_internal_index: usize,
+) -> Result<(), TypeEntry> {
+    let type_index = main_module_entry.function_entries[function_internal_index].type_index;
+    let type_entry = &main_module_entry.type_entries[type_index];
+
+    if type_entry.params.is_empty()
+        && (type_entry.results.is_empty() || type_entry.results == [OperandDataType::I32])
+    {
+        Ok(())
+    } else {
+        Err(type_entry.to_owned())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-
     use pretty_assertions::assert_eq;
 
     use anc_assembler::assembler::assemble_module_node;
@@ -588,10 +1311,19 @@ mod tests {
     use anc_parser_asm::parser::parse_from_str;
 
     use crate::{
-        dynamic_linker::dynamic_link, static_linker::static_link, DEFAULT_ENTRY_FUNCTION_NAME,
+        dynamic_linker::{dynamic_link, dynamic_relink},
+        elf_verifier::tests::build_elf_file,
+        interface_fingerprint::build_interface_fingerprint_entries,
+        static_linker::static_link,
+        test_helpers::{make_module_entry, make_runtime_dependency, make_share_dependency},
+        ExternalSymbolNamespace, LinkErrorType, LinkerError, LinkerWarning,
+        DEFAULT_ENTRY_FUNCTION_NAME,
     };
 
-    use super::sort_modules_by_dependent_deepth;
+    use super::{
+        group_modules_by_dependent_deepth, sort_modules_by_dependent_deepth,
+        split_dynamic_link_module_name,
+    };
 
     fn assemble_submodules(
         submodules: &[(/* fullname */ &str, /* source */ &str)],
@@ -638,6 +1370,7 @@ mod tests {
             &submodule_entries,
         )
         .unwrap()
+        .0
     }
 
     fn build_index(
@@ -645,7 +1378,16 @@ mod tests {
         dynamic_link_module_entries: &[DynamicLinkModuleEntry],
     ) -> ImageIndexEntry {
         sort_modules_by_dependent_deepth(image_common_entries).unwrap();
-        dynamic_link(image_common_entries, dynamic_link_module_entries).unwrap()
+        let (image_index_entry, _, _) = dynamic_link(
+            image_common_entries,
+            dynamic_link_module_entries,
+            &[],
+            ExternalSymbolNamespace::LibraryQualified,
+            &[],
+            &[],
+        )
+        .unwrap();
+        image_index_entry
     }
 
     #[test]
@@ -706,32 +1448,126 @@ mod tests {
         );
 
         // test dangling module
-        // todo
+        let mut modules = vec![
+            make_module_entry("a", &["b"]),
+            make_module_entry("b", &[]),
+            make_module_entry("c", &[]),
+        ];
+
+        assert!(matches!(
+            sort_modules_by_dependent_deepth(&mut modules),
+            Err(LinkerError {
+                error_type: LinkErrorType::DanglingModule(name)
+            }) if name == "c"
+        ));
     }
 
     #[test]
-    fn test_build_index_functions_and_data() {
-        // app, module index = 0
-        let module_app = build_module(
-            "app",
-            &[(
-                "app",
-                r#"
-import fn std::add(i32,i32) -> i32  // func pub idx: 0 (target mod idx: 2, target func inter idx: 0)
-import fn math::inc(i32) -> i32     // func pub idx: 1 (target mod idx: 1, target func inter idx: 0)
-import fn std::sub(i32,i32) -> i32  // func pub idx: 2 (target mod idx: 2, target func inter idx: 1)
-
-import uninit data std::errno type i32      // data pub idx: 2 (target mod idx: 2, target data idx: 0, section: 2)
-import readonly data math::MAGIC type i32   // data pub idx: 0 (target mod idx: 1, target data idx: 0, section: 0)
-import readonly data math::POWER type i32   // data pub idx: 1 (target mod idx: 1, target data idx: 1, section: 0)
-
-data foo:i32 = 11                   // data pub idx: 3 (target mod idx: 0, target data idx: 0, section: 1)
-data bar:i32 = 13                   // data pub idx: 4 (target mod idx: 0, target data idx: 1, section: 1)
+    fn test_group_modules() {
+        let make_module_entry = |name: &str, dependency_names: &[&str]| {
+            let import_module_entries = dependency_names
+                .iter()
+                .map(|item| make_runtime_dependency(item))
+                .collect::<Vec<_>>();
+            make_module_entry(name, EffectiveVersion::new(0, 0, 0), import_module_entries)
+        };
 
-fn _start() {                       // func pub idx: 3 (target mod idx: 0, target func inter idx: 0)
-    when ne_i32(
-        data_load_i32_s(MAGIC)      // data pub idx: 0
-        imm_i32(42))
+        let modules = vec![
+            make_module_entry("a", &["e", "b", "c", "d"]),
+            make_module_entry("e", &["f"]),
+            make_module_entry("b", &["j"]),
+            make_module_entry("c", &["g"]),
+            make_module_entry("d", &["g", "h"]),
+            make_module_entry("f", &["d"]),
+            make_module_entry("g", &["j"]),
+            make_module_entry("h", &["i"]),
+            make_module_entry("i", &["j"]),
+            make_module_entry("j", &[]),
+        ];
+
+        let groups = group_modules_by_dependent_deepth(&modules).unwrap();
+
+        assert_eq!(
+            groups
+                .iter()
+                .map(|group| group
+                    .iter()
+                    .map(|module_index| modules[*module_index].name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(","))
+                .collect::<Vec<_>>(),
+            vec!["j", "i", "g,h", "d", "f", "b,c,e", "a"]
+        );
+    }
+
+    #[test]
+    fn test_group_modules_with_side_by_side_versions() {
+        let make_module_entry =
+            |name: &str, version: EffectiveVersion, dependencies: &[(&str, &str)]| {
+                let import_module_entries = dependencies
+                    .iter()
+                    .map(|(dependency_name, dependency_version)| {
+                        make_share_dependency(dependency_name, dependency_version)
+                    })
+                    .collect::<Vec<_>>();
+                make_module_entry(name, version, import_module_entries)
+            };
+
+        // the two versions of "json" have different major versions,
+        // they are still ordered by version in the group.
+        let modules = vec![
+            make_module_entry(
+                "app",
+                EffectiveVersion::new(1, 0, 0),
+                &[("a", "1.0.0"), ("b", "1.0.0")],
+            ),
+            make_module_entry("a", EffectiveVersion::new(1, 0, 0), &[("json", "2.0.0")]),
+            make_module_entry("b", EffectiveVersion::new(1, 0, 0), &[("json", "1.4.0")]),
+            make_module_entry("json", EffectiveVersion::new(2, 0, 0), &[]),
+            make_module_entry("json", EffectiveVersion::new(1, 4, 0), &[]),
+        ];
+
+        let groups = group_modules_by_dependent_deepth(&modules).unwrap();
+
+        assert_eq!(
+            groups
+                .iter()
+                .map(|group| group
+                    .iter()
+                    .map(|module_index| format!(
+                        "{}@{}",
+                        modules[*module_index].name, modules[*module_index].version
+                    ))
+                    .collect::<Vec<_>>()
+                    .join(","))
+                .collect::<Vec<_>>(),
+            vec!["json@1.4.0,json@2.0.0", "a@1.0.0,b@1.0.0", "app@1.0.0"]
+        );
+    }
+
+    #[test]
+    fn test_build_index_functions_and_data() {
+        // app, module index = 0
+        let module_app = build_module(
+            "app",
+            &[(
+                "app",
+                r#"
+import fn std::add(i32,i32) -> i32  // func pub idx: 0 (target mod idx: 2, target func inter idx: 0)
+import fn math::inc(i32) -> i32     // func pub idx: 1 (target mod idx: 1, target func inter idx: 0)
+import fn std::sub(i32,i32) -> i32  // func pub idx: 2 (target mod idx: 2, target func inter idx: 1)
+
+import uninit data std::errno type i32      // data pub idx: 2 (target mod idx: 2, target data idx: 0, section: 2)
+import readonly data math::MAGIC type i32   // data pub idx: 0 (target mod idx: 1, target data idx: 0, section: 0)
+import readonly data math::POWER type i32   // data pub idx: 1 (target mod idx: 1, target data idx: 1, section: 0)
+
+data foo:i32 = 11                   // data pub idx: 3 (target mod idx: 0, target data idx: 0, section: 1)
+data bar:i32 = 13                   // data pub idx: 4 (target mod idx: 0, target data idx: 1, section: 1)
+
+fn _start() {                       // func pub idx: 3 (target mod idx: 0, target func inter idx: 0)
+    when ne_i32(
+        data_load_i32_s(MAGIC)      // data pub idx: 0
+        imm_i32(42))
         panic(1)
 
     when ne_i32(
@@ -1187,6 +2023,122 @@ fn do_this() -> i32 {
         );
     }
 
+    #[test]
+    fn test_build_index_external_functions_with_global_namespace() {
+        let build_modules = |std_source: &str| {
+            let module_app = build_module(
+                "app",
+                &[(
+                    "app",
+                    r#"
+external fn foo::getuid() -> i32
+
+fn _start() -> i32 {
+    extcall(getuid)
+}
+"#,
+                )],
+                &[ImportModuleEntry::new(
+                    "std".to_owned(),
+                    Box::new(ModuleDependency::Runtime),
+                )],
+                &[ExternalLibraryEntry::new(
+                    "foo".to_owned(),
+                    Box::new(ExternalLibraryDependency::System("foo".to_owned())),
+                )],
+            );
+
+            let module_std = build_module(
+                "std",
+                &[("std", std_source)],
+                &[],
+                &[ExternalLibraryEntry::new(
+                    "bar".to_owned(),
+                    Box::new(ExternalLibraryDependency::System("bar".to_owned())),
+                )],
+            );
+
+            let mut image_common_entries = vec![module_app, module_std];
+            sort_modules_by_dependent_deepth(&mut image_common_entries).unwrap();
+            image_common_entries
+        };
+
+        let dynamic_link_module_entries = vec![
+            DynamicLinkModuleEntry::new("app".to_owned(), Box::new(ModuleLocation::Embed)),
+            DynamicLinkModuleEntry::new("std".to_owned(), Box::new(ModuleLocation::Runtime)),
+        ];
+
+        let image_common_entries = build_modules(
+            r#"
+external fn bar::getuid() -> i32
+external fn bar::getpid() -> i32
+
+fn do_this() -> i32 {
+    extcall(getuid)
+    extcall(getpid)
+}
+"#,
+        );
+
+        let (image_index_entry, _, warnings) = dynamic_link(
+            &image_common_entries,
+            &dynamic_link_module_entries,
+            &[],
+            ExternalSymbolNamespace::Global,
+            &[],
+            &[],
+        )
+        .unwrap();
+
+        // "bar::getuid" is merged into "foo::getuid"
+        assert!(warnings.contains(&LinkerWarning::ExternalFunctionMerged(
+            "getuid".to_owned(),
+            "foo".to_owned(),
+            "bar".to_owned()
+        )));
+
+        assert_eq!(
+            image_index_entry.unified_external_function_entries,
+            vec![
+                ExternalFunctionEntry::new("getuid".to_owned(), 0, 0),
+                ExternalFunctionEntry::new("getpid".to_owned(), 1, 0),
+            ]
+        );
+
+        assert_eq!(
+            image_index_entry.external_function_index_entries[1].index_entries,
+            vec![
+                ExternalFunctionIndexEntry::new(0),
+                ExternalFunctionIndexEntry::new(1),
+            ]
+        );
+
+        // the functions with the same name have different types
+        let image_common_entries = build_modules(
+            r#"
+external fn bar::getuid(i32) -> i32
+
+fn do_this() -> i32 {
+    extcall(getuid, imm_i32(0x11))
+}
+"#,
+        );
+
+        assert!(matches!(
+            dynamic_link(
+                &image_common_entries,
+                &dynamic_link_module_entries,
+                &[],
+                ExternalSymbolNamespace::Global,
+                &[],
+                &[],
+            ),
+            Err(LinkerError {
+                error_type: LinkErrorType::ExternalFunctionConflict(name)
+            }) if name == "getuid"
+        ));
+    }
+
     #[test]
     fn test_build_index_entry_points() {
         let module_hello = build_module(
@@ -1297,4 +2249,701 @@ fn empty() {
             ]
         );
     }
+
+    #[test]
+    fn test_build_index_entry_points_with_type_mismatch() {
+        let module_hello = build_module(
+            "hello",
+            &[(
+                "hello",
+                r#"
+fn _start(n:i32)->i32 {
+    local_load_i32_s(n)
+}
+"#,
+            )],
+            &[],
+            &[],
+        );
+
+        let mut image_common_entries = vec![module_hello];
+        let dynamic_link_module_entries = vec![DynamicLinkModuleEntry::new(
+            "hello".to_owned(),
+            Box::new(ModuleLocation::Runtime),
+        )];
+
+        sort_modules_by_dependent_deepth(&mut image_common_entries).unwrap();
+        let link_result = dynamic_link(
+            &image_common_entries,
+            &dynamic_link_module_entries,
+            &[],
+            ExternalSymbolNamespace::LibraryQualified,
+            &[],
+            &[],
+        );
+
+        // the signatures are in the same notation
+        assert_eq!(
+            link_result.as_ref().unwrap_err().to_string(),
+            "The entry point function \"hello::_start\" has the signature \"(i32) -> (i32)\", \
+but it should be \"() -> ()\" or \"() -> (i32)\"."
+        );
+
+        assert!(matches!(
+            link_result,
+            Err(LinkerError {
+                error_type: LinkErrorType::EntryPointTypeMismatch(name, type_entry)
+            }) if name == "hello::_start"
+                && type_entry == TypeEntry::new(
+                    vec![OperandDataType::I32],
+                    vec![OperandDataType::I32]
+                )
+        ));
+
+        let module_hello = build_module(
+            "hello",
+            &[(
+                "hello::tests::foo",
+                r#"
+fn test_a()->(i32, i32) {
+    imm_i32(0x11)
+    imm_i32(0x13)
+}
+"#,
+            )],
+            &[],
+            &[],
+        );
+
+        let mut image_common_entries = vec![module_hello];
+        sort_modules_by_dependent_deepth(&mut image_common_entries).unwrap();
+        let link_result = dynamic_link(
+            &image_common_entries,
+            &dynamic_link_module_entries,
+            &[],
+            ExternalSymbolNamespace::LibraryQualified,
+            &[],
+            &[],
+        );
+
+        assert!(matches!(
+            link_result,
+            Err(LinkerError {
+                error_type: LinkErrorType::UnitTestTypeMismatch(name, _)
+            }) if name == "hello::tests::foo::test_a"
+        ));
+    }
+
+    #[test]
+    fn test_build_index_with_module_version_mismatch() {
+        let build_modules = |required_version: &str, provided_version: EffectiveVersion| {
+            let module_app = build_module(
+                "app",
+                &[(
+                    "app",
+                    r#"
+import fn std::add(i32,i32) -> i32
+
+fn _start() -> i32 {
+    call(add, imm_i32(0x11), imm_i32(0x13))
+}
+"#,
+                )],
+                &[make_share_dependency("std", required_version)],
+                &[],
+            );
+
+            let mut module_std = build_module(
+                "std",
+                &[(
+                    "std",
+                    r#"
+pub fn add(left:i32, right:i32) -> i32 {
+    add_i32(
+        local_load_i32_s(left)
+        local_load_i32_s(right))
+}
+"#,
+                )],
+                &[],
+                &[],
+            );
+            module_std.version = provided_version;
+
+            let mut image_common_entries = vec![module_app, module_std];
+            sort_modules_by_dependent_deepth(&mut image_common_entries).unwrap();
+            image_common_entries
+        };
+
+        let dynamic_link_module_entries = vec![
+            DynamicLinkModuleEntry::new("app".to_owned(), Box::new(ModuleLocation::Embed)),
+            DynamicLinkModuleEntry::new("std".to_owned(), Box::new(ModuleLocation::Runtime)),
+        ];
+
+        // the provided version is newer than the required one
+        let image_common_entries = build_modules("1.2.0", EffectiveVersion::new(1, 3, 1));
+        let (_, _, warnings) = dynamic_link(
+            &image_common_entries,
+            &dynamic_link_module_entries,
+            &[],
+            ExternalSymbolNamespace::LibraryQualified,
+            &[],
+            &[],
+        )
+        .unwrap();
+
+        assert_eq!(
+            warnings,
+            vec![LinkerWarning::DependencyUpgraded(
+                "std".to_owned(),
+                "app".to_owned(),
+                "1.2.0".to_owned(),
+                "1.3.1".to_owned()
+            )]
+        );
+
+        // the provided version is older than the required one
+        let image_common_entries = build_modules("1.2.0", EffectiveVersion::new(1, 1, 0));
+        assert_eq!(
+            dynamic_link(
+                &image_common_entries,
+                &dynamic_link_module_entries,
+                &[],
+                ExternalSymbolNamespace::LibraryQualified,
+                &[],
+                &[]
+            )
+            .unwrap_err()
+            .to_string(),
+            "The dependent module \"std\" is required by \"app\" to be compatible with \
+version \"1.2.0\", but the provided version is \"1.1.0\"."
+        );
+        assert!(matches!(
+            dynamic_link(&image_common_entries, &dynamic_link_module_entries, &[], ExternalSymbolNamespace::LibraryQualified, &[], &[]),
+            Err(LinkerError {
+                error_type: LinkErrorType::ModuleVersionMismatch(name, importer, required, provided)
+            }) if name == "std" && importer == "app" && required == "1.2.0" && provided == EffectiveVersion::new(1, 1, 0)
+        ));
+
+        // the major versions are different
+        let image_common_entries = build_modules("1.2.0", EffectiveVersion::new(2, 0, 0));
+        assert!(matches!(
+            dynamic_link(&image_common_entries, &dynamic_link_module_entries, &[], ExternalSymbolNamespace::LibraryQualified, &[], &[]),
+            Err(LinkerError {
+                error_type: LinkErrorType::ModuleVersionMismatch(name, importer, _, _)
+            }) if name == "std" && importer == "app"
+        ));
+    }
+
+    #[test]
+    fn test_build_index_with_unused_import_module() {
+        let module_app = build_module(
+            "app",
+            &[(
+                "app",
+                r#"
+import fn std::add(i32,i32) -> i32
+
+fn _start() -> i32 {
+    call(add, imm_i32(0x11), imm_i32(0x13))
+}
+"#,
+            )],
+            &[
+                ImportModuleEntry::new("std".to_owned(), Box::new(ModuleDependency::Runtime)),
+                ImportModuleEntry::new("math".to_owned(), Box::new(ModuleDependency::Runtime)),
+            ],
+            &[],
+        );
+
+        let module_std = build_module(
+            "std",
+            &[(
+                "std",
+                r#"
+pub fn add(left:i32, right:i32) -> i32 {
+    add_i32(
+        local_load_i32_s(left)
+        local_load_i32_s(right))
+}
+"#,
+            )],
+            &[],
+            &[],
+        );
+
+        let module_math = build_module(
+            "math",
+            &[(
+                "math",
+                r#"
+pub fn zero() -> i32 {
+    imm_i32(0)
+}
+"#,
+            )],
+            &[],
+            &[],
+        );
+
+        let dynamic_link_module_entries = vec![
+            DynamicLinkModuleEntry::new("app".to_owned(), Box::new(ModuleLocation::Embed)),
+            DynamicLinkModuleEntry::new("std".to_owned(), Box::new(ModuleLocation::Runtime)),
+            DynamicLinkModuleEntry::new("math".to_owned(), Box::new(ModuleLocation::Runtime)),
+        ];
+
+        let mut image_common_entries = vec![module_app, module_std, module_math];
+        sort_modules_by_dependent_deepth(&mut image_common_entries).unwrap();
+
+        let (_, _, warnings) = dynamic_link(
+            &image_common_entries,
+            &dynamic_link_module_entries,
+            &[],
+            ExternalSymbolNamespace::LibraryQualified,
+            &[],
+            &[],
+        )
+        .unwrap();
+
+        assert_eq!(
+            warnings,
+            vec![LinkerWarning::UnusedImportModule("math".to_owned())]
+        );
+    }
+
+    #[test]
+    fn test_build_index_with_system_library_resolution() {
+        let library_directory = std::env::temp_dir().join(format!(
+            "anc-linker-dynamic-link-system-library-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&library_directory).unwrap();
+        let library_search_directories = vec![library_directory.clone()];
+        std::fs::write(
+            library_directory.join("libfoo.so.1.2"),
+            build_elf_file(&[("getuid", true)], Some("libfoo.so.1")),
+        )
+        .unwrap();
+
+        let module_app = build_module(
+            "app",
+            &[(
+                "app",
+                r#"
+external fn foo::getuid() -> i32
+
+fn _start() -> i32 {
+    extcall(getuid)
+}
+"#,
+            )],
+            &[],
+            &[ExternalLibraryEntry::new(
+                "foo".to_owned(),
+                Box::new(ExternalLibraryDependency::System("foo".to_owned())),
+            )],
+        );
+
+        let image_common_entries = vec![module_app];
+        let dynamic_link_module_entries = vec![DynamicLinkModuleEntry::new(
+            "app".to_owned(),
+            Box::new(ModuleLocation::Embed),
+        )];
+
+        let (image_index_entry, _, _) = dynamic_link(
+            &image_common_entries,
+            &dynamic_link_module_entries,
+            &[],
+            ExternalSymbolNamespace::LibraryQualified,
+            &library_search_directories,
+            &[],
+        )
+        .unwrap();
+
+        assert_eq!(
+            image_index_entry.unified_external_library_entries,
+            vec![ExternalLibraryEntry::new(
+                "foo".to_owned(),
+                Box::new(ExternalLibraryDependency::System("libfoo.so.1".to_owned()))
+            )]
+        );
+
+        // the library cannot be found
+        assert!(matches!(
+            dynamic_link(
+                &image_common_entries,
+                &dynamic_link_module_entries,
+                &[],
+                ExternalSymbolNamespace::LibraryQualified,
+                &[library_directory.join("lib")],
+                &[],
+            ),
+            Err(LinkerError {
+                error_type: LinkErrorType::ExternalLibraryNotFound(library_name)
+            }) if library_name == "foo"
+        ));
+
+        std::fs::remove_dir_all(&library_directory).unwrap();
+    }
+
+    #[test]
+    fn test_build_index_with_unexpected_image_type() {
+        // the object file is not finalized by the static linker
+        let mut image_common_entries = assemble_submodules(
+            &[(
+                "app",
+                r#"
+fn _start() -> () {
+    nop()
+}
+"#,
+            )],
+            &[],
+            &[],
+        );
+
+        let dynamic_link_module_entries = vec![DynamicLinkModuleEntry::new(
+            "app".to_owned(),
+            Box::new(ModuleLocation::Embed),
+        )];
+
+        sort_modules_by_dependent_deepth(&mut image_common_entries).unwrap();
+
+        assert!(matches!(
+            dynamic_link(&image_common_entries, &dynamic_link_module_entries, &[], ExternalSymbolNamespace::LibraryQualified, &[], &[]),
+            Err(LinkerError {
+                error_type: LinkErrorType::UnexpectedImageType(name, ImageType::ObjectFile)
+            }) if name == "app"
+        ));
+    }
+
+    #[test]
+    fn test_dynamic_relink() {
+        let module_app = build_module(
+            "app",
+            &[(
+                "app",
+                r#"
+import fn std::add(i32,i32) -> i32
+
+fn _start() -> i32 {
+    call(add, imm_i32(0x11), imm_i32(0x13))
+}
+"#,
+            )],
+            &[ImportModuleEntry::new(
+                "std".to_owned(),
+                Box::new(ModuleDependency::Runtime),
+            )],
+            &[],
+        );
+
+        let build_module_std = |source: &str| build_module("std", &[("std", source)], &[], &[]);
+
+        let module_std = build_module_std(
+            r#"
+pub fn add(left:i32, right:i32) -> i32 {
+    add_i32(
+        local_load_i32_s(left)
+        local_load_i32_s(right))
+}
+"#,
+        );
+
+        let dynamic_link_module_entries = vec![
+            DynamicLinkModuleEntry::new("app".to_owned(), Box::new(ModuleLocation::Embed)),
+            DynamicLinkModuleEntry::new("std".to_owned(), Box::new(ModuleLocation::Runtime)),
+        ];
+
+        let mut image_common_entries = vec![module_app, module_std];
+        let previous_index_entry =
+            build_index(&mut image_common_entries, &dynamic_link_module_entries);
+        let recorded_interface_fingerprint_entries =
+            build_interface_fingerprint_entries(&image_common_entries);
+
+        // the updated module inserts a function before "add"
+        image_common_entries[1] = build_module_std(
+            r#"
+fn helper() -> () {
+    nop()
+}
+
+pub fn add(left:i32, right:i32) -> i32 {
+    add_i32(
+        local_load_i32_s(left)
+        local_load_i32_s(right))
+}
+"#,
+        );
+
+        let (index_entry, interface_fingerprint_entries, warnings) = dynamic_relink(
+            &previous_index_entry,
+            &image_common_entries,
+            1,
+            &dynamic_link_module_entries[1],
+            &[],
+            ExternalSymbolNamespace::LibraryQualified,
+            &[],
+            &recorded_interface_fingerprint_entries,
+        )
+        .unwrap();
+
+        assert_eq!(
+            interface_fingerprint_entries,
+            build_interface_fingerprint_entries(&image_common_entries)
+        );
+        assert!(warnings.is_empty());
+
+        assert_eq!(
+            index_entry.function_index_list_entries[0].index_entries,
+            vec![
+                FunctionIndexEntry::new(1, 1), // std::add
+                FunctionIndexEntry::new(0, 0), // app::_start
+            ]
+        );
+
+        // same as the full linking
+        assert_eq!(
+            index_entry,
+            dynamic_link(
+                &image_common_entries,
+                &dynamic_link_module_entries,
+                &[],
+                ExternalSymbolNamespace::LibraryQualified,
+                &[],
+                &[]
+            )
+            .unwrap()
+            .0
+        );
+
+        // the updated module breaks the existing binding
+        image_common_entries[1] = build_module_std(
+            r#"
+pub fn add(left:i32) -> i32 {
+    local_load_i32_s(left)
+}
+"#,
+        );
+
+        assert!(matches!(
+            dynamic_relink(
+                &previous_index_entry,
+                &image_common_entries,
+                1,
+                &dynamic_link_module_entries[1],
+                &[],
+                ExternalSymbolNamespace::LibraryQualified,
+                &[], &[],
+            ),
+            Err(LinkerError {
+                error_type: LinkErrorType::ImportFunctionTypeMismatch(name)
+            }) if name == "std::add"
+        ));
+
+        // the interface of the updated module is changed since the last linking
+        assert!(matches!(
+            dynamic_relink(
+                &previous_index_entry,
+                &image_common_entries,
+                1,
+                &dynamic_link_module_entries[1],
+                &[],
+                ExternalSymbolNamespace::LibraryQualified,
+                &[],
+                &recorded_interface_fingerprint_entries,
+            ),
+            Err(LinkerError {
+                error_type: LinkErrorType::InterfaceFingerprintMismatch(name)
+            }) if name == "std"
+        ));
+
+        // the updated module does not replace the old one
+        assert!(matches!(
+            dynamic_relink(
+                &previous_index_entry,
+                &image_common_entries,
+                1,
+                &DynamicLinkModuleEntry::new("math".to_owned(), Box::new(ModuleLocation::Runtime)),
+                &[],
+                ExternalSymbolNamespace::LibraryQualified,
+                &[], &[],
+            ),
+            Err(LinkerError {
+                error_type: LinkErrorType::RelinkModuleMismatch(name)
+            }) if name == "std"
+        ));
+
+        // the updated module uses different external functions
+        image_common_entries[1] = build_module(
+            "std",
+            &[(
+                "std",
+                r#"
+external fn foo::a(i32,i32)-> i32
+
+pub fn add(left:i32, right:i32) -> i32 {
+    extcall(a, local_load_i32_s(left), local_load_i32_s(right))
+}
+"#,
+            )],
+            &[],
+            &[ExternalLibraryEntry::new(
+                "foo".to_owned(),
+                Box::new(ExternalLibraryDependency::System("foo".to_owned())),
+            )],
+        );
+
+        assert!(matches!(
+            dynamic_relink(
+                &previous_index_entry,
+                &image_common_entries,
+                1,
+                &dynamic_link_module_entries[1],
+                &[],
+                ExternalSymbolNamespace::LibraryQualified,
+                &[],
+                &[],
+            ),
+            Err(LinkerError {
+                error_type: LinkErrorType::RelinkModuleMismatch(name)
+            }) if name == "std"
+        ));
+    }
+
+    #[test]
+    fn test_build_index_with_side_by_side_module_versions() {
+        let build_module_with_version =
+            |module_name: &str,
+             source: &str,
+             import_module_entries: &[ImportModuleEntry],
+             version: EffectiveVersion| {
+                let mut module_entry = build_module(
+                    module_name,
+                    &[(module_name, source)],
+                    import_module_entries,
+                    &[],
+                );
+                module_entry.version = version;
+                module_entry
+            };
+
+        // app, module index = 0
+        let module_app = build_module_with_version(
+            "app",
+            r#"
+import fn json::ver() -> i32
+import fn util::get() -> i32
+
+fn _start() -> i32 {
+    add_i32(
+        call(ver)
+        call(get))
+}
+"#,
+            &[
+                make_share_dependency("json", "1.2.0"),
+                make_share_dependency("util", "1.0.0"),
+            ],
+            EffectiveVersion::new(0, 0, 0),
+        );
+
+        // json 2.x, module index = 3
+        let module_json2 = build_module_with_version(
+            "json",
+            r#"
+pub fn ver() -> i32 {
+    imm_i32(2)
+}
+"#,
+            &[],
+            EffectiveVersion::new(2, 0, 0),
+        );
+
+        // json 1.x, module index = 1
+        let module_json1 = build_module_with_version(
+            "json",
+            r#"
+pub fn ver() -> i32 {
+    imm_i32(1)
+}
+"#,
+            &[],
+            EffectiveVersion::new(1, 4, 1),
+        );
+
+        // util, module index = 2
+        let module_util = build_module_with_version(
+            "util",
+            r#"
+import fn json::ver() -> i32
+
+pub fn get() -> i32 {
+    call(ver)
+}
+"#,
+            &[make_share_dependency("json", "2.0.0")],
+            EffectiveVersion::new(1, 0, 0),
+        );
+
+        let mut image_common_entries = vec![module_app, module_json2, module_json1, module_util];
+        let dynamic_link_module_entries = vec![
+            DynamicLinkModuleEntry::new("app".to_owned(), Box::new(ModuleLocation::Embed)),
+            DynamicLinkModuleEntry::new("util".to_owned(), Box::new(ModuleLocation::Runtime)),
+            DynamicLinkModuleEntry::new("json@2.0.0".to_owned(), Box::new(ModuleLocation::Runtime)),
+            DynamicLinkModuleEntry::new("json@1.4.1".to_owned(), Box::new(ModuleLocation::Runtime)),
+        ];
+
+        let image_index_entry =
+            build_index(&mut image_common_entries, &dynamic_link_module_entries);
+
+        assert_eq!(
+            image_common_entries
+                .iter()
+                .map(|item| format!("{}@{}", item.name, item.version))
+                .collect::<Vec<_>>(),
+            vec!["app@0.0.0", "json@1.4.1", "util@1.0.0", "json@2.0.0"]
+        );
+
+        // check function index list
+        assert_eq!(
+            image_index_entry.function_index_list_entries[0].index_entries,
+            vec![
+                FunctionIndexEntry::new(1, 0),
+                FunctionIndexEntry::new(2, 0),
+                FunctionIndexEntry::new(0, 0),
+            ]
+        );
+
+        assert_eq!(
+            image_index_entry.function_index_list_entries[2].index_entries,
+            vec![FunctionIndexEntry::new(3, 0), FunctionIndexEntry::new(2, 0),]
+        );
+
+        // check dynamic link module list
+        assert_eq!(
+            image_index_entry.dynamic_link_module_entries,
+            vec![
+                DynamicLinkModuleEntry::new("app".to_owned(), Box::new(ModuleLocation::Embed)),
+                DynamicLinkModuleEntry::new(
+                    "json@1.4.1".to_owned(),
+                    Box::new(ModuleLocation::Runtime)
+                ),
+                DynamicLinkModuleEntry::new("util".to_owned(), Box::new(ModuleLocation::Runtime)),
+                DynamicLinkModuleEntry::new(
+                    "json@2.0.0".to_owned(),
+                    Box::new(ModuleLocation::Runtime)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_dynamic_link_module_name() {
+        assert_eq!(
+            split_dynamic_link_module_name("json@1.4.1"),
+            ("json", Some("1.4.1"))
+        );
+        assert_eq!(split_dynamic_link_module_name("json"), ("json", None));
+    }
 }
@@ -0,0 +1,695 @@
+// Copyright (c) 2026 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+use std::path::{Path, PathBuf};
+
+use anc_image::entry::{ExternalFunctionEntry, ExternalLibraryEntry};
+use anc_isa::ExternalLibraryDependency;
+
+use crate::{system_library::find_system_library_file, LinkErrorType, LinkerError};
+
+const ELF_MAGIC: &[u8] = b"\x7fELF";
+
+const ELF_CLASS_32: u8 = 1;
+const ELF_CLASS_64: u8 = 2;
+const ELF_DATA_LITTLE_ENDIAN: u8 = 1;
+const ELF_DATA_BIG_ENDIAN: u8 = 2;
+
+const SECTION_TYPE_DYNAMIC: u32 = 6;
+const SECTION_TYPE_DYNSYM: u32 = 11;
+const DYNAMIC_TAG_NULL: usize = 0;
+const DYNAMIC_TAG_SONAME: usize = 14;
+const SECTION_INDEX_UNDEFINED: u16 = 0;
+const SYMBOL_BINDING_GLOBAL: u8 = 1;
+const SYMBOL_BINDING_WEAK: u8 = 2;
+
+/// A minimal reader of the ELF file, only the fields which are
+/// required to read the dynamic symbol table and the dynamic section
+/// are supported.
+struct ElfReader<'a> {
+    binary: &'a [u8],
+    is_64bit: bool,
+    is_little_endian: bool,
+}
+
+/// The fields of the section header which are used by the reader.
+struct SectionHeader {
+    section_type: u32,
+    offset: usize,
+    size: usize,
+    link: usize,
+    entry_size: usize,
+}
+
+impl<'a> ElfReader<'a> {
+    fn new(binary: &'a [u8]) -> Result<Self, String> {
+        // the identification bytes "e_ident"
+        if binary.len() < 0x10 || !binary.starts_with(ELF_MAGIC) {
+            return Err("Not an ELF file.".to_owned());
+        }
+
+        let (is_64bit, header_size) = match binary[4] {
+            ELF_CLASS_32 => (false, 0x34),
+            ELF_CLASS_64 => (true, 0x40),
+            _ => return Err("Unsupported ELF class.".to_owned()),
+        };
+
+        if binary.len() < header_size {
+            return Err("Incomplete ELF header.".to_owned());
+        }
+
+        let is_little_endian = match binary[5] {
+            ELF_DATA_LITTLE_ENDIAN => true,
+            ELF_DATA_BIG_ENDIAN => false,
+            _ => return Err("Unsupported ELF data encoding.".to_owned()),
+        };
+
+        Ok(Self {
+            binary,
+            is_64bit,
+            is_little_endian,
+        })
+    }
+
+    fn read_bytes(&self, offset: usize, length: usize) -> Result<&[u8], String> {
+        offset
+            .checked_add(length)
+            .and_then(|end| self.binary.get(offset..end))
+            .ok_or_else(|| format!("Offset 0x{:x} is out of range.", offset))
+    }
+
+    fn read_u16(&self, offset: usize) -> Result<u16, String> {
+        let bytes: [u8; 2] = self.read_bytes(offset, 2)?.try_into().unwrap();
+        Ok(if self.is_little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    }
+
+    fn read_u32(&self, offset: usize) -> Result<u32, String> {
+        let bytes: [u8; 4] = self.read_bytes(offset, 4)?.try_into().unwrap();
+        Ok(if self.is_little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn read_u64(&self, offset: usize) -> Result<u64, String> {
+        let bytes: [u8; 8] = self.read_bytes(offset, 8)?.try_into().unwrap();
+        Ok(if self.is_little_endian {
+            u64::from_le_bytes(bytes)
+        } else {
+            u64::from_be_bytes(bytes)
+        })
+    }
+
+    /// Reads the "Elf32_Addr/Elf32_Off" or "Elf64_Addr/Elf64_Off" field.
+    fn read_word(&self, offset: usize) -> Result<usize, String> {
+        if self.is_64bit {
+            Ok(self.read_u64(offset)? as usize)
+        } else {
+            Ok(self.read_u32(offset)? as usize)
+        }
+    }
+
+    /// Calculates the offset `base + index * size` with overflow checking,
+    /// the result should be within the file, so that adding a field
+    /// offset to it does not overflow.
+    fn calculate_offset(&self, base: usize, index: usize, size: usize) -> Result<usize, String> {
+        index
+            .checked_mul(size)
+            .and_then(|item| base.checked_add(item))
+            .filter(|offset| *offset <= self.binary.len())
+            .ok_or_else(|| {
+                format!(
+                    "Offset 0x{:x} + 0x{:x} * 0x{:x} is out of range.",
+                    base, index, size
+                )
+            })
+    }
+
+    fn read_c_string(&self, offset: usize) -> Result<String, String> {
+        let bytes = self
+            .binary
+            .get(offset..)
+            .ok_or_else(|| format!("Offset 0x{:x} is out of range.", offset))?;
+        let length = bytes
+            .iter()
+            .position(|byte| *byte == 0)
+            .ok_or_else(|| "Unterminated string.".to_owned())?;
+        Ok(String::from_utf8_lossy(&bytes[..length]).into_owned())
+    }
+
+    // the layout of the header and section header
+    // https://refspecs.linuxfoundation.org/elf/gabi4+/ch4.eheader.html
+    fn read_section_header(&self, section_index: usize) -> Result<SectionHeader, String> {
+        let (section_header_offset, section_header_size) = if self.is_64bit {
+            (self.read_word(0x28)?, self.read_u16(0x3a)? as usize)
+        } else {
+            (self.read_word(0x20)?, self.read_u16(0x2e)? as usize)
+        };
+
+        let base =
+            self.calculate_offset(section_header_offset, section_index, section_header_size)?;
+        if self.is_64bit {
+            Ok(SectionHeader {
+                section_type: self.read_u32(base + 0x04)?,
+                offset: self.read_word(base + 0x18)?,
+                size: self.read_word(base + 0x20)?,
+                link: self.read_u32(base + 0x28)? as usize,
+                entry_size: self.read_word(base + 0x38)?,
+            })
+        } else {
+            Ok(SectionHeader {
+                section_type: self.read_u32(base + 0x04)?,
+                offset: self.read_word(base + 0x10)?,
+                size: self.read_word(base + 0x14)?,
+                link: self.read_u32(base + 0x18)? as usize,
+                entry_size: self.read_word(base + 0x24)?,
+            })
+        }
+    }
+
+    /// Finds the first section of the specified type.
+    fn find_section(&self, section_type: u32) -> Result<Option<SectionHeader>, String> {
+        let section_count = if self.is_64bit {
+            self.read_u16(0x3c)? as usize
+        } else {
+            self.read_u16(0x30)? as usize
+        };
+
+        for section_index in 0..section_count {
+            let section_header = self.read_section_header(section_index)?;
+            if section_header.section_type == section_type {
+                return Ok(Some(section_header));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// Reads the names of the defined global and weak symbols in the
+/// dynamic symbol table (i.e. the ".dynsym" section) of an ELF
+/// shared object.
+///
+/// Both 32-bit and 64-bit, little-endian and big-endian files are supported.
+pub fn read_elf_dynamic_symbols(binary: &[u8]) -> Result<Vec<String>, String> {
+    let reader = ElfReader::new(binary)?;
+
+    let Some(symbol_table) = reader.find_section(SECTION_TYPE_DYNSYM)? else {
+        return Err("The dynamic symbol table is not found.".to_owned());
+    };
+
+    if symbol_table.entry_size == 0 {
+        return Err("Invalid symbol entry size.".to_owned());
+    }
+
+    let string_table = reader.read_section_header(symbol_table.link)?;
+
+    let mut symbol_names = vec![];
+    for symbol_index in 0..(symbol_table.size / symbol_table.entry_size) {
+        let base =
+            reader.calculate_offset(symbol_table.offset, symbol_index, symbol_table.entry_size)?;
+
+        // (st_name, st_info, st_shndx)
+        let (name_offset, info, section_index) = if reader.is_64bit {
+            (
+                reader.read_u32(base)? as usize,
+                reader.read_bytes(base + 0x04, 1)?[0],
+                reader.read_u16(base + 0x06)?,
+            )
+        } else {
+            (
+                reader.read_u32(base)? as usize,
+                reader.read_bytes(base + 0x0c, 1)?[0],
+                reader.read_u16(base + 0x0e)?,
+            )
+        };
+
+        let binding = info >> 4;
+        if section_index == SECTION_INDEX_UNDEFINED
+            || (binding != SYMBOL_BINDING_GLOBAL && binding != SYMBOL_BINDING_WEAK)
+        {
+            continue;
+        }
+
+        let name = reader.read_c_string(reader.calculate_offset(
+            string_table.offset,
+            name_offset,
+            1,
+        )?)?;
+        if !name.is_empty() {
+            symbol_names.push(name);
+        }
+    }
+
+    Ok(symbol_names)
+}
+
+/// Reads the "DT_SONAME" entry of the dynamic section (i.e. the ".dynamic"
+/// section) of an ELF shared object, returns `None` if the
+/// shared object has no soname.
+pub fn read_elf_soname(binary: &[u8]) -> Result<Option<String>, String> {
+    let reader = ElfReader::new(binary)?;
+
+    let Some(dynamic_section) = reader.find_section(SECTION_TYPE_DYNAMIC)? else {
+        return Ok(None);
+    };
+
+    let string_table = reader.read_section_header(dynamic_section.link)?;
+
+    // each entry is (d_tag, d_val), both are words.
+    let entry_size = if reader.is_64bit { 16 } else { 8 };
+    let word_size = entry_size / 2;
+
+    for entry_index in 0..(dynamic_section.size / entry_size) {
+        let base = reader.calculate_offset(dynamic_section.offset, entry_index, entry_size)?;
+        let tag = reader.read_word(base)?;
+
+        if tag == DYNAMIC_TAG_NULL {
+            break;
+        }
+
+        if tag == DYNAMIC_TAG_SONAME {
+            let name_offset = reader.read_word(base + word_size)?;
+            return reader
+                .read_c_string(reader.calculate_offset(string_table.offset, name_offset, 1)?)
+                .map(Some);
+        }
+    }
+
+    Ok(None)
+}
+
+/// Finds the file of the external library on the local filesystem.
+///
+/// - "Local" libraries: the path of the dependency (it has been
+///   normalized by the static linker).
+/// - "System" libraries: searched in the specified directories,
+///   see `find_system_library_file`.
+///
+/// Returns `None` for "Remote" and "Share" libraries, they are resolved
+/// by the runtime.
+pub fn find_external_library_file(
+    external_library_entry: &ExternalLibraryEntry,
+    library_search_directories: &[PathBuf],
+) -> Option<PathBuf> {
+    match external_library_entry.value.as_ref() {
+        ExternalLibraryDependency::Local(local) => {
+            let path = PathBuf::from(&local.path);
+            path.is_file().then_some(path)
+        }
+        ExternalLibraryDependency::System(value) => {
+            find_system_library_file(value, library_search_directories)
+        }
+        ExternalLibraryDependency::Remote(_) | ExternalLibraryDependency::Share(_) => None,
+    }
+}
+
+fn read_external_library_symbols(
+    library_name: &str,
+    file_path: &Path,
+) -> Result<Vec<String>, LinkerError> {
+    let binary = std::fs::read(file_path).map_err(|error| {
+        LinkerError::new(LinkErrorType::InvalidExternalLibrary(
+            library_name.to_owned(),
+            error.to_string(),
+        ))
+    })?;
+
+    read_elf_dynamic_symbols(&binary).map_err(|message| {
+        LinkerError::new(LinkErrorType::InvalidExternalLibrary(
+            library_name.to_owned(),
+            message,
+        ))
+    })
+}
+
+/// Verifies that the external functions are exported by the libraries
+/// on the local filesystem, so that the unresolved external functions are
+/// reported at link time instead of being reported by the runtime
+/// when the library is loaded.
+///
+/// The entries can be the ones of an `ImageCommonEntry`, or the unified
+/// entries of an `ImageIndexEntry`. Only the "Local" and "System" libraries
+/// are verified, see `find_external_library_file`.
+pub fn verify_external_functions(
+    external_library_entries: &[ExternalLibraryEntry],
+    external_function_entries: &[ExternalFunctionEntry],
+    library_search_directories: &[PathBuf],
+) -> Result<(), LinkerError> {
+    for (external_library_index, external_library_entry) in
+        external_library_entries.iter().enumerate()
+    {
+        let library_name = &external_library_entry.name;
+
+        let file_path = match external_library_entry.value.as_ref() {
+            ExternalLibraryDependency::Local(_) | ExternalLibraryDependency::System(_) => {
+                find_external_library_file(external_library_entry, library_search_directories)
+                    .ok_or_else(|| {
+                        LinkerError::new(LinkErrorType::ExternalLibraryNotFound(
+                            library_name.to_owned(),
+                        ))
+                    })?
+            }
+            ExternalLibraryDependency::Remote(_) | ExternalLibraryDependency::Share(_) => {
+                continue;
+            }
+        };
+
+        let symbol_names = read_external_library_symbols(library_name, &file_path)?;
+
+        for external_function_entry in external_function_entries
+            .iter()
+            .filter(|item| item.external_library_index == external_library_index)
+        {
+            if !symbol_names.contains(&external_function_entry.name) {
+                return Err(LinkerError::new(LinkErrorType::ExternalFunctionNotFound(
+                    external_function_entry.name.to_owned(),
+                    library_name.to_owned(),
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::collections::HashMap;
+
+    use anc_image::entry::{ExternalFunctionEntry, ExternalLibraryEntry};
+    use anc_isa::{DependencyCondition, DependencyLocal, ExternalLibraryDependency};
+    use pretty_assertions::assert_eq;
+
+    use crate::{LinkErrorType, LinkerError};
+
+    use super::{read_elf_dynamic_symbols, read_elf_soname, verify_external_functions};
+
+    /// Builds a 64-bit little-endian ELF file which contains only the
+    /// null section, the ".dynstr", the ".dynsym" and the ".dynamic" sections.
+    pub(crate) fn build_elf_file(
+        symbols: &[(/* name */ &str, /* is_defined */ bool)],
+        soname_opt: Option<&str>,
+    ) -> Vec<u8> {
+        build_elf_file_with_encoding(true, true, symbols, soname_opt)
+    }
+
+    /// Builds an ELF file of the specified class and data encoding,
+    /// see `build_elf_file`.
+    fn build_elf_file_with_encoding(
+        is_64bit: bool,
+        is_little_endian: bool,
+        symbols: &[(/* name */ &str, /* is_defined */ bool)],
+        soname_opt: Option<&str>,
+    ) -> Vec<u8> {
+        let (header_size, section_header_size, symbol_size) = if is_64bit {
+            (0x40, 0x40, 0x18)
+        } else {
+            (0x34, 0x28, 0x10)
+        };
+        let word_size = if is_64bit { 8 } else { 4 };
+        let dynamic_entry_size = word_size * 2;
+
+        let to_u16 = |value: u16| {
+            if is_little_endian {
+                value.to_le_bytes()
+            } else {
+                value.to_be_bytes()
+            }
+        };
+
+        let to_u32 = |value: u32| {
+            if is_little_endian {
+                value.to_le_bytes()
+            } else {
+                value.to_be_bytes()
+            }
+        };
+
+        let to_word = |value: usize| {
+            if is_64bit {
+                let bytes = if is_little_endian {
+                    (value as u64).to_le_bytes()
+                } else {
+                    (value as u64).to_be_bytes()
+                };
+                bytes.to_vec()
+            } else {
+                to_u32(value as u32).to_vec()
+            }
+        };
+
+        // (st_info, st_shndx)
+        let (symbol_info_offset, symbol_section_index_offset) =
+            if is_64bit { (0x04, 0x06) } else { (0x0c, 0x0e) };
+
+        let mut dynstr = vec![0u8];
+        let mut dynsym = vec![0u8; symbol_size]; // the null symbol
+        for (name, is_defined) in symbols {
+            let name_offset = dynstr.len() as u32;
+            dynstr.extend_from_slice(name.as_bytes());
+            dynstr.push(0);
+
+            let mut symbol = vec![0u8; symbol_size];
+            symbol[0..4].copy_from_slice(&to_u32(name_offset));
+            symbol[symbol_info_offset] = (1 << 4) | 2; // STB_GLOBAL, STT_FUNC
+            let section_index: u16 = if *is_defined { 1 } else { 0 };
+            symbol[symbol_section_index_offset..symbol_section_index_offset + 2]
+                .copy_from_slice(&to_u16(section_index));
+            dynsym.extend_from_slice(&symbol);
+        }
+
+        let mut dynamic = vec![];
+        if let Some(soname) = soname_opt {
+            let name_offset = dynstr.len();
+            dynstr.extend_from_slice(soname.as_bytes());
+            dynstr.push(0);
+            dynamic.extend_from_slice(&to_word(14)); // DT_SONAME
+            dynamic.extend_from_slice(&to_word(name_offset));
+        }
+        dynamic.extend_from_slice(&vec![0u8; dynamic_entry_size]); // DT_NULL
+
+        let dynstr_offset = header_size;
+        let dynsym_offset = dynstr_offset + dynstr.len();
+        let dynamic_offset = dynsym_offset + dynsym.len();
+        let section_header_offset = dynamic_offset + dynamic.len();
+
+        // (e_shoff, e_ehsize, e_shentsize, e_shnum)
+        let (
+            section_header_offset_offset,
+            header_size_offset,
+            section_header_size_offset,
+            section_count_offset,
+        ) = if is_64bit {
+            (0x28, 0x34, 0x3a, 0x3c)
+        } else {
+            (0x20, 0x28, 0x2e, 0x30)
+        };
+
+        let mut binary = vec![0u8; header_size];
+        binary[0..4].copy_from_slice(b"\x7fELF");
+        binary[4] = if is_64bit { 2 } else { 1 }; // ELFCLASS64 or ELFCLASS32
+        binary[5] = if is_little_endian { 1 } else { 2 }; // ELFDATA2LSB or ELFDATA2MSB
+        binary[6] = 1; // EV_CURRENT
+        binary[0x10..0x12].copy_from_slice(&to_u16(3)); // ET_DYN
+        binary[section_header_offset_offset..section_header_offset_offset + word_size]
+            .copy_from_slice(&to_word(section_header_offset));
+        binary[header_size_offset..header_size_offset + 2]
+            .copy_from_slice(&to_u16(header_size as u16));
+        binary[section_header_size_offset..section_header_size_offset + 2]
+            .copy_from_slice(&to_u16(section_header_size as u16));
+        binary[section_count_offset..section_count_offset + 2].copy_from_slice(&to_u16(4));
+
+        binary.extend_from_slice(&dynstr);
+        binary.extend_from_slice(&dynsym);
+        binary.extend_from_slice(&dynamic);
+
+        // (sh_offset, sh_size, sh_link, sh_entsize)
+        let (offset_offset, size_offset, link_offset, entry_size_offset) = if is_64bit {
+            (0x18, 0x20, 0x28, 0x38)
+        } else {
+            (0x10, 0x14, 0x18, 0x24)
+        };
+
+        let build_section_header =
+            |section_type: u32, offset: usize, size: usize, link: u32, entry_size: usize| {
+                let mut header = vec![0u8; section_header_size];
+                header[0x04..0x08].copy_from_slice(&to_u32(section_type));
+                header[offset_offset..offset_offset + word_size].copy_from_slice(&to_word(offset));
+                header[size_offset..size_offset + word_size].copy_from_slice(&to_word(size));
+                header[link_offset..link_offset + 4].copy_from_slice(&to_u32(link));
+                header[entry_size_offset..entry_size_offset + word_size]
+                    .copy_from_slice(&to_word(entry_size));
+                header
+            };
+
+        binary.extend_from_slice(&vec![0u8; section_header_size]);
+        binary.extend_from_slice(&build_section_header(3, dynstr_offset, dynstr.len(), 0, 0));
+        binary.extend_from_slice(&build_section_header(
+            11,
+            dynsym_offset,
+            dynsym.len(),
+            1,
+            symbol_size,
+        ));
+        binary.extend_from_slice(&build_section_header(
+            6,
+            dynamic_offset,
+            dynamic.len(),
+            1,
+            dynamic_entry_size,
+        ));
+
+        binary
+    }
+
+    #[test]
+    fn test_read_elf_dynamic_symbols() {
+        let binary = build_elf_file(
+            &[("getuid", true), ("malloc", false), ("getpid", true)],
+            None,
+        );
+
+        assert_eq!(
+            read_elf_dynamic_symbols(&binary).unwrap(),
+            vec!["getuid".to_owned(), "getpid".to_owned()]
+        );
+
+        assert!(read_elf_dynamic_symbols(b"#!/bin/sh\necho hello world\n").is_err());
+        assert!(read_elf_dynamic_symbols(&binary[..0x50]).is_err());
+
+        // the header of the 64-bit ELF file is 0x40 bytes
+        assert_eq!(
+            read_elf_dynamic_symbols(&binary[..0x38]),
+            Err("Incomplete ELF header.".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_read_elf_32bit_big_endian() {
+        let binary = build_elf_file_with_encoding(
+            false,
+            false,
+            &[("getuid", true), ("malloc", false), ("getpid", true)],
+            Some("libfoo.so.1"),
+        );
+
+        assert_eq!(
+            read_elf_dynamic_symbols(&binary).unwrap(),
+            vec!["getuid".to_owned(), "getpid".to_owned()]
+        );
+
+        assert_eq!(
+            read_elf_soname(&binary).unwrap(),
+            Some("libfoo.so.1".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_read_elf_with_overflowing_offset() {
+        // the section header offset (e_shoff) is close to the max value
+        let mut binary = build_elf_file(&[("getuid", true)], Some("libfoo.so.1"));
+        binary[0x28..0x30].copy_from_slice(&(u64::MAX - 0x10).to_le_bytes());
+
+        assert!(read_elf_dynamic_symbols(&binary).is_err());
+        assert!(read_elf_soname(&binary).is_err());
+
+        // the 32-bit symbol table offset (sh_offset of ".dynsym") is
+        // close to the max value
+        let mut binary = build_elf_file_with_encoding(false, false, &[("getuid", true)], None);
+        let section_header_offset =
+            u32::from_be_bytes(binary[0x20..0x24].try_into().unwrap()) as usize;
+        let dynsym_header_offset = section_header_offset + 0x28 * 2;
+        binary[dynsym_header_offset + 0x10..dynsym_header_offset + 0x14]
+            .copy_from_slice(&(u32::MAX - 0x08).to_be_bytes());
+
+        assert!(read_elf_dynamic_symbols(&binary).is_err());
+    }
+
+    #[test]
+    fn test_read_elf_soname() {
+        assert_eq!(
+            read_elf_soname(&build_elf_file(&[("getuid", true)], Some("libfoo.so.1"))).unwrap(),
+            Some("libfoo.so.1".to_owned())
+        );
+
+        assert_eq!(
+            read_elf_soname(&build_elf_file(&[("getuid", true)], None)).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_verify_external_functions() {
+        let library_directory =
+            std::env::temp_dir().join(format!("anc-linker-elf-verifier-{}", std::process::id()));
+        std::fs::create_dir_all(&library_directory).unwrap();
+
+        let local_file_path = library_directory.join("libbar.so.1");
+        std::fs::write(
+            library_directory.join("libfoo.so"),
+            build_elf_file(&[("getuid", true), ("getpid", true)], None),
+        )
+        .unwrap();
+        std::fs::write(&local_file_path, build_elf_file(&[("sum", true)], None)).unwrap();
+
+        let external_library_entries = vec![
+            ExternalLibraryEntry::new(
+                "foo".to_owned(),
+                Box::new(ExternalLibraryDependency::System("foo".to_owned())),
+            ),
+            ExternalLibraryEntry::new(
+                "bar".to_owned(),
+                Box::new(ExternalLibraryDependency::Local(Box::new(
+                    DependencyLocal {
+                        path: local_file_path.to_str().unwrap().to_owned(),
+                        condition: DependencyCondition::True,
+                        parameters: HashMap::default(),
+                    },
+                ))),
+            ),
+        ];
+
+        let library_search_directories = vec![library_directory.clone()];
+
+        assert!(verify_external_functions(
+            &external_library_entries,
+            &[
+                ExternalFunctionEntry::new("getuid".to_owned(), 0, 0),
+                ExternalFunctionEntry::new("sum".to_owned(), 1, 0),
+            ],
+            &library_search_directories,
+        )
+        .is_ok());
+
+        // the function is exported by another library
+        assert!(matches!(
+            verify_external_functions(
+                &external_library_entries,
+                &[ExternalFunctionEntry::new("sum".to_owned(), 0, 0)],
+                &library_search_directories,
+            ),
+            Err(LinkerError {
+                error_type: LinkErrorType::ExternalFunctionNotFound(function_name, library_name)
+            }) if function_name == "sum" && library_name == "foo"
+        ));
+
+        // the library is not in the search directories
+        assert!(matches!(
+            verify_external_functions(&external_library_entries, &[], &[]),
+            Err(LinkerError {
+                error_type: LinkErrorType::ExternalLibraryNotFound(library_name)
+            }) if library_name == "foo"
+        ));
+
+        std::fs::remove_dir_all(&library_directory).unwrap();
+    }
+}
@@ -0,0 +1,137 @@
+// Copyright (c) 2026 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+use crate::{LinkErrorType, LinkerError};
+
+/// The call count of a function, collected by the VM profiler.
+#[derive(Debug, PartialEq, Clone)]
+pub struct FunctionProfileEntry {
+    pub full_name: String,
+    pub count: u64,
+}
+
+impl FunctionProfileEntry {
+    pub fn new(full_name: String, count: u64) -> Self {
+        Self { full_name, count }
+    }
+}
+
+/// Parses the profile file, each line is the full name of a function
+/// and its call count separated by spaces, e.g.
+///
+/// ```text
+/// # comment
+/// hello::main 1
+/// hello::foo::add 1024
+/// ```
+///
+/// Empty lines and lines starting with "#" are ignored.
+pub fn parse_function_profile(text: &str) -> Result<Vec<FunctionProfileEntry>, LinkerError> {
+    let mut entries = vec![];
+
+    for (line_index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let parts = line.split_whitespace().collect::<Vec<_>>();
+        let count_opt = match parts.as_slice() {
+            [_, count] => count.parse::<u64>().ok(),
+            _ => None,
+        };
+
+        let Some(count) = count_opt else {
+            return Err(LinkerError::new(LinkErrorType::InvalidFunctionProfile(
+                line_index + 1,
+                line.to_owned(),
+            )));
+        };
+
+        entries.push(FunctionProfileEntry::new(parts[0].to_owned(), count));
+    }
+
+    Ok(entries)
+}
+
+/// Calculates the order of functions, the hot functions (i.e. the functions
+/// in the profile with non-zero call count) are placed at the start by
+/// descending call count, and the cold functions are placed at the end
+/// in the original order.
+///
+/// The result is the original internal indices in the new order.
+pub fn calculate_function_order(
+    function_full_names: &[&str],
+    function_profile_entries: &[FunctionProfileEntry],
+) -> Vec<usize> {
+    let get_count = |full_name: &str| {
+        function_profile_entries
+            .iter()
+            .find(|item| item.full_name == full_name)
+            .map_or(0, |item| item.count)
+    };
+
+    let counts = function_full_names
+        .iter()
+        .map(|full_name| get_count(full_name))
+        .collect::<Vec<_>>();
+
+    // the sort is stable, so the cold functions keep the original order.
+    let mut order = (0..function_full_names.len()).collect::<Vec<_>>();
+    order.sort_by_key(|index| std::cmp::Reverse(counts[*index]));
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{LinkErrorType, LinkerError};
+
+    use super::{calculate_function_order, parse_function_profile, FunctionProfileEntry};
+
+    #[test]
+    fn test_parse_function_profile() {
+        assert_eq!(
+            parse_function_profile(
+                r#"
+# hot functions
+hello::main 1
+hello::foo::add   1024
+"#
+            )
+            .unwrap(),
+            vec![
+                FunctionProfileEntry::new("hello::main".to_owned(), 1),
+                FunctionProfileEntry::new("hello::foo::add".to_owned(), 1024),
+            ]
+        );
+
+        assert!(matches!(
+            parse_function_profile("hello::main 1\nhello::foo::add many"),
+            Err(LinkerError {
+                error_type: LinkErrorType::InvalidFunctionProfile(2, line)
+            }) if line == "hello::foo::add many"
+        ));
+    }
+
+    #[test]
+    fn test_calculate_function_order() {
+        let profile = vec![
+            FunctionProfileEntry::new("hello::b".to_owned(), 5),
+            FunctionProfileEntry::new("hello::d".to_owned(), 100),
+            FunctionProfileEntry::new("hello::e".to_owned(), 0),
+        ];
+
+        assert_eq!(
+            calculate_function_order(
+                &["hello::a", "hello::b", "hello::c", "hello::d", "hello::e"],
+                &profile
+            ),
+            vec![3, 1, 0, 2, 4]
+        );
+    }
+}
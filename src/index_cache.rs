@@ -0,0 +1,545 @@
+// Copyright (c) 2026 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use anc_image::{
+    entry::{DynamicLinkModuleEntry, ImageCommonEntry, ImageIndexEntry, ModuleLocation},
+    entry_reader::read_image_file,
+    entry_writer::{write_image_file, write_object_file},
+};
+use anc_isa::{ExternalLibraryDependency, ModuleDependency, ParameterValue};
+
+use crate::{
+    compute_stable_hash,
+    dynamic_linker::dynamic_link,
+    interface_fingerprint::{
+        build_interface_fingerprint_entries, check_interface_fingerprints,
+        InterfaceFingerprintEntry,
+    },
+    ExternalSymbolNamespace, LinkerError, LinkerWarning,
+};
+
+const CACHE_FILE_EXTENSION: &str = "anci";
+
+/// The length of the inputs digest at the beginning of the cache file.
+const CACHE_FILE_DIGEST_LENGTH: usize = 16;
+
+/// Encodes the inputs of `dynamic_link` into bytes in a canonical form,
+/// i.e. the same inputs always produce the same bytes:
+///
+/// - the modules are encoded in the object file format, except the `parameters`
+///   of the dependencies, which are `HashMap` and have no fixed order, so they
+///   are encoded separately with the keys sorted.
+/// - the active flags are sorted and deduplicated.
+fn encode_link_inputs(
+    // should be sorted entries
+    image_common_entries: &[ImageCommonEntry],
+    dynamic_link_module_entries: &[DynamicLinkModuleEntry],
+    active_flags: &[&str],
+    external_symbol_namespace: ExternalSymbolNamespace,
+    system_library_search_directories: &[PathBuf],
+) -> std::io::Result<Vec<u8>> {
+    let mut bytes: Vec<u8> = vec![];
+
+    encode_length(&mut bytes, image_common_entries.len());
+    for image_common_entry in image_common_entries {
+        let mut canonical_entry = image_common_entry.clone();
+        let mut parameters_list: Vec<HashMap<String, ParameterValue>> = vec![];
+
+        for import_module_entry in &mut canonical_entry.import_module_entries {
+            match import_module_entry.module_dependency.as_mut() {
+                ModuleDependency::Local(dependency) => {
+                    parameters_list.push(std::mem::take(&mut dependency.parameters))
+                }
+                ModuleDependency::Remote(dependency) => {
+                    parameters_list.push(std::mem::take(&mut dependency.parameters))
+                }
+                ModuleDependency::Share(dependency) => {
+                    parameters_list.push(std::mem::take(&mut dependency.parameters))
+                }
+                ModuleDependency::Runtime | ModuleDependency::Module => {
+                    parameters_list.push(HashMap::default())
+                }
+            }
+        }
+
+        for external_library_entry in &mut canonical_entry.external_library_entries {
+            match external_library_entry.value.as_mut() {
+                ExternalLibraryDependency::Local(dependency) => {
+                    parameters_list.push(std::mem::take(&mut dependency.parameters))
+                }
+                ExternalLibraryDependency::Remote(dependency) => {
+                    parameters_list.push(std::mem::take(&mut dependency.parameters))
+                }
+                ExternalLibraryDependency::Share(dependency) => {
+                    parameters_list.push(std::mem::take(&mut dependency.parameters))
+                }
+                ExternalLibraryDependency::System(_) => parameters_list.push(HashMap::default()),
+            }
+        }
+
+        let mut object_bytes: Vec<u8> = vec![];
+        write_object_file(&canonical_entry, false, &mut object_bytes)?;
+        encode_bytes(&mut bytes, &object_bytes);
+
+        for parameters in &parameters_list {
+            let sorted_parameters = parameters.iter().collect::<BTreeMap<_, _>>();
+            encode_length(&mut bytes, sorted_parameters.len());
+            for (name, value) in sorted_parameters {
+                let value_text = ason::to_string(value).map_err(std::io::Error::other)?;
+                encode_bytes(&mut bytes, name.as_bytes());
+                encode_bytes(&mut bytes, value_text.as_bytes());
+            }
+        }
+    }
+
+    encode_length(&mut bytes, dynamic_link_module_entries.len());
+    for dynamic_link_module_entry in dynamic_link_module_entries {
+        encode_bytes(&mut bytes, dynamic_link_module_entry.name.as_bytes());
+        bytes.push(match dynamic_link_module_entry.module_location.as_ref() {
+            ModuleLocation::Embed => 0,
+            ModuleLocation::Runtime => 1,
+        });
+    }
+
+    let mut sorted_active_flags = active_flags.to_vec();
+    sorted_active_flags.sort();
+    sorted_active_flags.dedup();
+
+    encode_length(&mut bytes, sorted_active_flags.len());
+    for active_flag in sorted_active_flags {
+        encode_bytes(&mut bytes, active_flag.as_bytes());
+    }
+
+    bytes.push(match external_symbol_namespace {
+        ExternalSymbolNamespace::LibraryQualified => 0,
+        ExternalSymbolNamespace::Global => 1,
+    });
+
+    encode_length(&mut bytes, system_library_search_directories.len());
+    for directory in system_library_search_directories {
+        encode_bytes(&mut bytes, directory.to_string_lossy().as_bytes());
+    }
+
+    Ok(bytes)
+}
+
+fn encode_length(bytes: &mut Vec<u8>, length: usize) {
+    bytes.extend_from_slice(&(length as u64).to_le_bytes());
+}
+
+fn encode_bytes(bytes: &mut Vec<u8>, data: &[u8]) {
+    encode_length(bytes, data.len());
+    bytes.extend_from_slice(data);
+}
+
+/// Computes the FNV-1a (128-bit) hash of the given bytes, it is stored in
+/// the cache file to detect the collisions of the 64-bit file name hash.
+fn compute_inputs_digest(bytes: &[u8]) -> u128 {
+    let mut hash: u128 = 0x6c62272e07bb014262b821756295c58d;
+    for byte in bytes {
+        hash ^= *byte as u128;
+        hash = hash.wrapping_mul(0x0000000001000000000000000000013b);
+    }
+    hash
+}
+
+/// Computes the hash of the inputs of `dynamic_link`, see `encode_link_inputs`
+/// for the inputs which are covered.
+pub fn compute_link_inputs_hash(
+    // should be sorted entries
+    image_common_entries: &[ImageCommonEntry],
+    dynamic_link_module_entries: &[DynamicLinkModuleEntry],
+    active_flags: &[&str],
+    external_symbol_namespace: ExternalSymbolNamespace,
+    system_library_search_directories: &[PathBuf],
+) -> std::io::Result<u64> {
+    let bytes = encode_link_inputs(
+        image_common_entries,
+        dynamic_link_module_entries,
+        active_flags,
+        external_symbol_namespace,
+        system_library_search_directories,
+    )?;
+    Ok(compute_stable_hash(&bytes))
+}
+
+/// Gets the path of the cache file of the given inputs hash.
+pub fn get_cache_file_path(cache_directory: &Path, inputs_hash: u64) -> PathBuf {
+    cache_directory.join(format!("{:016x}.{}", inputs_hash, CACHE_FILE_EXTENSION))
+}
+
+/// Same as `dynamic_link`, but the result is cached in the specified directory.
+///
+/// The cache file consists of the digest of the inputs (128-bit, little-endian)
+/// and an application image with the index. The cached index is returned directly
+/// if the digest matches, otherwise the modules are linked and the result is
+/// written to the cache.
+///
+/// The cache is best-effort: an unreadable or mismatched cache file causes
+/// relinking, and the failure of writing the cache file is reported as
+/// a warning. Note that the changes of the system library files are not
+/// detected, remove the cache directory after the system libraries are upgraded.
+///
+/// The interface fingerprints and the warnings are returned next to the index,
+/// same as `dynamic_link`, except that the warnings of linking are not
+/// reproduced when the index is cached, call `dynamic_link` directly
+/// for diagnosing.
+pub fn dynamic_link_with_cache(
+    cache_directory: &Path,
+    // should be sorted entries
+    image_common_entries: &[ImageCommonEntry],
+    dynamic_link_module_entries: &[DynamicLinkModuleEntry],
+    active_flags: &[&str],
+    external_symbol_namespace: ExternalSymbolNamespace,
+    system_library_search_directories: &[PathBuf],
+    recorded_interface_fingerprint_entries: &[InterfaceFingerprintEntry],
+) -> Result<
+    (
+        ImageIndexEntry,
+        Vec<InterfaceFingerprintEntry>,
+        Vec<LinkerWarning>,
+    ),
+    LinkerError,
+> {
+    let inputs_result = encode_link_inputs(
+        image_common_entries,
+        dynamic_link_module_entries,
+        active_flags,
+        external_symbol_namespace,
+        system_library_search_directories,
+    );
+
+    // the inputs can not be encoded, e.g. a parameter value is not serializable,
+    // link without the cache.
+    let inputs_bytes = match inputs_result {
+        Ok(bytes) => bytes,
+        Err(error) => {
+            let (image_index_entry, interface_fingerprint_entries, mut warnings) = dynamic_link(
+                image_common_entries,
+                dynamic_link_module_entries,
+                active_flags,
+                external_symbol_namespace,
+                system_library_search_directories,
+                recorded_interface_fingerprint_entries,
+            )?;
+            warnings.push(LinkerWarning::IndexCacheNotWritten(
+                cache_directory.to_string_lossy().to_string(),
+                error.to_string(),
+            ));
+            return Ok((image_index_entry, interface_fingerprint_entries, warnings));
+        }
+    };
+
+    let inputs_digest = compute_inputs_digest(&inputs_bytes);
+    let cache_file_path = get_cache_file_path(cache_directory, compute_stable_hash(&inputs_bytes));
+
+    if let Ok(binary) = std::fs::read(&cache_file_path) {
+        if let Some((digest_bytes, image_binary)) =
+            binary.split_first_chunk::<CACHE_FILE_DIGEST_LENGTH>()
+        {
+            if u128::from_le_bytes(*digest_bytes) == inputs_digest {
+                if let Ok((_, image_index_entry)) = read_image_file(image_binary) {
+                    // the fingerprints are checked even if the index is cached, because
+                    // the records may be updated since the index was cached.
+                    check_interface_fingerprints(
+                        image_common_entries,
+                        recorded_interface_fingerprint_entries,
+                    )?;
+                    return Ok((
+                        image_index_entry,
+                        build_interface_fingerprint_entries(image_common_entries),
+                        vec![],
+                    ));
+                }
+            }
+        }
+    }
+
+    let (image_index_entry, interface_fingerprint_entries, mut warnings) = dynamic_link(
+        image_common_entries,
+        dynamic_link_module_entries,
+        active_flags,
+        external_symbol_namespace,
+        system_library_search_directories,
+        recorded_interface_fingerprint_entries,
+    )?;
+
+    // the index is stored with the application module,
+    // i.e. the cache file contains a complete application image.
+    let write_result = std::fs::create_dir_all(cache_directory).and_then(|_| {
+        let file = File::create(&cache_file_path)?;
+        let mut writer = BufWriter::new(file);
+        writer.write_all(&inputs_digest.to_le_bytes())?;
+        write_image_file(&image_common_entries[0], &image_index_entry, &mut writer)?;
+        writer.flush()
+    });
+
+    if let Err(error) = write_result {
+        warnings.push(LinkerWarning::IndexCacheNotWritten(
+            cache_file_path.to_string_lossy().to_string(),
+            error.to_string(),
+        ));
+    }
+
+    Ok((image_index_entry, interface_fingerprint_entries, warnings))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use anc_image::entry::{DynamicLinkModuleEntry, ImportModuleEntry, ModuleLocation};
+    use anc_isa::{
+        DependencyCondition, DependencyShare, EffectiveVersion, ModuleDependency, ParameterValue,
+    };
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        dynamic_linker::dynamic_link, test_helpers::build_module, ExternalSymbolNamespace,
+        LinkerWarning,
+    };
+
+    use super::{compute_link_inputs_hash, dynamic_link_with_cache, get_cache_file_path};
+
+    #[test]
+    fn test_dynamic_link_with_cache() {
+        let image_common_entries = vec![build_module(
+            "app",
+            r#"
+fn _start() -> () {
+    nop()
+}
+"#,
+            &[],
+            EffectiveVersion::new(0, 0, 0),
+        )];
+
+        let dynamic_link_module_entries = vec![DynamicLinkModuleEntry::new(
+            "app".to_owned(),
+            Box::new(ModuleLocation::Embed),
+        )];
+
+        let cache_directory =
+            std::env::temp_dir().join(format!("anc-linker-index-cache-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&cache_directory);
+
+        let inputs_hash = compute_link_inputs_hash(
+            &image_common_entries,
+            &dynamic_link_module_entries,
+            &[],
+            ExternalSymbolNamespace::LibraryQualified,
+            &[],
+        )
+        .unwrap();
+        let cache_file_path = get_cache_file_path(&cache_directory, inputs_hash);
+
+        // the order and duplication of flags do not affect the hash
+        assert_eq!(
+            compute_link_inputs_hash(
+                &image_common_entries,
+                &dynamic_link_module_entries,
+                &["b", "a", "b"],
+                ExternalSymbolNamespace::LibraryQualified,
+                &[]
+            )
+            .unwrap(),
+            compute_link_inputs_hash(
+                &image_common_entries,
+                &dynamic_link_module_entries,
+                &["a", "b"],
+                ExternalSymbolNamespace::LibraryQualified,
+                &[]
+            )
+            .unwrap()
+        );
+        assert_ne!(
+            compute_link_inputs_hash(
+                &image_common_entries,
+                &dynamic_link_module_entries,
+                &["a"],
+                ExternalSymbolNamespace::LibraryQualified,
+                &[]
+            )
+            .unwrap(),
+            inputs_hash
+        );
+        assert_ne!(
+            compute_link_inputs_hash(
+                &image_common_entries,
+                &dynamic_link_module_entries,
+                &[],
+                ExternalSymbolNamespace::Global,
+                &[]
+            )
+            .unwrap(),
+            inputs_hash
+        );
+
+        let (expected_index_entry, expected_fingerprint_entries, _) = dynamic_link(
+            &image_common_entries,
+            &dynamic_link_module_entries,
+            &[],
+            ExternalSymbolNamespace::LibraryQualified,
+            &[],
+            &[],
+        )
+        .unwrap();
+
+        // cache miss
+        assert!(!cache_file_path.exists());
+        let (index_entry0, fingerprint_entries0, warnings0) = dynamic_link_with_cache(
+            &cache_directory,
+            &image_common_entries,
+            &dynamic_link_module_entries,
+            &[],
+            ExternalSymbolNamespace::LibraryQualified,
+            &[],
+            &[],
+        )
+        .unwrap();
+        assert_eq!(index_entry0, expected_index_entry);
+        assert_eq!(fingerprint_entries0, expected_fingerprint_entries);
+        assert!(warnings0.is_empty());
+        assert!(cache_file_path.exists());
+
+        // cache hit
+        let (index_entry1, fingerprint_entries1, warnings1) = dynamic_link_with_cache(
+            &cache_directory,
+            &image_common_entries,
+            &dynamic_link_module_entries,
+            &[],
+            ExternalSymbolNamespace::LibraryQualified,
+            &[],
+            &[],
+        )
+        .unwrap();
+        assert_eq!(index_entry1, expected_index_entry);
+        assert_eq!(fingerprint_entries1, expected_fingerprint_entries);
+        assert!(warnings1.is_empty());
+
+        // the cache file of a different digest (e.g. a collision of the file name hash)
+        // is ignored and overwritten.
+        let mut binary = std::fs::read(&cache_file_path).unwrap();
+        binary[0] ^= 0xff;
+        std::fs::write(&cache_file_path, &binary).unwrap();
+
+        let (index_entry2, _, _) = dynamic_link_with_cache(
+            &cache_directory,
+            &image_common_entries,
+            &dynamic_link_module_entries,
+            &[],
+            ExternalSymbolNamespace::LibraryQualified,
+            &[],
+            &[],
+        )
+        .unwrap();
+        assert_eq!(index_entry2, expected_index_entry);
+        assert_ne!(std::fs::read(&cache_file_path).unwrap(), binary);
+
+        std::fs::remove_dir_all(&cache_directory).unwrap();
+    }
+
+    #[test]
+    fn test_dynamic_link_with_unwritable_cache() {
+        let image_common_entries = vec![build_module(
+            "app",
+            r#"
+fn _start() -> () {
+    nop()
+}
+"#,
+            &[],
+            EffectiveVersion::new(0, 0, 0),
+        )];
+
+        let dynamic_link_module_entries = vec![DynamicLinkModuleEntry::new(
+            "app".to_owned(),
+            Box::new(ModuleLocation::Embed),
+        )];
+
+        // the cache directory is a file
+        let cache_directory = std::env::temp_dir().join(format!(
+            "anc-linker-index-cache-file-{}",
+            std::process::id()
+        ));
+        std::fs::write(&cache_directory, b"").unwrap();
+
+        let (_, _, warnings) = dynamic_link_with_cache(
+            &cache_directory,
+            &image_common_entries,
+            &dynamic_link_module_entries,
+            &[],
+            ExternalSymbolNamespace::LibraryQualified,
+            &[],
+            &[],
+        )
+        .unwrap();
+
+        assert!(matches!(
+            warnings.as_slice(),
+            [LinkerWarning::IndexCacheNotWritten(_, _)]
+        ));
+
+        std::fs::remove_file(&cache_directory).unwrap();
+    }
+
+    #[test]
+    fn test_compute_link_inputs_hash_with_parameters() {
+        let module = build_module(
+            "app",
+            r#"
+fn _start() -> () {
+    nop()
+}
+"#,
+            &[],
+            EffectiveVersion::new(0, 0, 0),
+        );
+
+        let with_parameters = |names: &[&str]| {
+            let mut parameters = HashMap::new();
+            for name in names {
+                parameters.insert(name.to_string(), ParameterValue::String(name.to_string()));
+            }
+
+            let mut image_common_entry = module.clone();
+            image_common_entry
+                .import_module_entries
+                .push(ImportModuleEntry::new(
+                    "json".to_owned(),
+                    Box::new(ModuleDependency::Share(Box::new(DependencyShare {
+                        version: "1.0.0".to_owned(),
+                        condition: DependencyCondition::True,
+                        parameters,
+                    }))),
+                ));
+
+            compute_link_inputs_hash(
+                &[image_common_entry],
+                &[],
+                &[],
+                ExternalSymbolNamespace::LibraryQualified,
+                &[],
+            )
+            .unwrap()
+        };
+
+        // the order of the parameters does not affect the hash
+        let names = ["a", "b", "c", "d", "e", "f", "g", "h"];
+        let mut reversed_names = names;
+        reversed_names.reverse();
+        assert_eq!(with_parameters(&names), with_parameters(&reversed_names));
+
+        assert_ne!(with_parameters(&names), with_parameters(&names[1..]));
+    }
+}
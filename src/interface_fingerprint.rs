@@ -0,0 +1,384 @@
+// Copyright (c) 2026 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+use anc_image::{entry::ImageCommonEntry, module_image::Visibility};
+use anc_isa::{DataSectionType, MemoryDataType, OperandDataType};
+
+use crate::{
+    compute_stable_hash, dynamic_linker::get_dynamic_link_module_name, LinkErrorType, LinkerError,
+};
+
+/// The interface fingerprint of a dependent module.
+///
+/// `ImageIndexEntry` has no place for the fingerprints, and adding one changes
+/// the image format which is defined by the `anc-image` crate, that is out of
+/// the scope of the linker. So the list of `InterfaceFingerprintEntry` is recorded
+/// alongside the index by the caller (e.g. the launcher) and passed to
+/// `dynamic_link` on the next linking.
+#[derive(Debug, PartialEq, Clone)]
+pub struct InterfaceFingerprintEntry {
+    /// The name of the `DynamicLinkModuleEntry`, i.e. the module name,
+    /// or "name@version" when several versions of a module are linked.
+    pub module_name: String,
+    pub fingerprint: u64,
+}
+
+impl InterfaceFingerprintEntry {
+    pub fn new(module_name: String, fingerprint: u64) -> Self {
+        Self {
+            module_name,
+            fingerprint,
+        }
+    }
+}
+
+/// Computes a stable hash of the exported interface (ABI) of a module, includes:
+///
+/// - the full name and signature of the public functions.
+/// - the full name, section, data type and length of the public data.
+///
+/// Private items and the order of items do not affect the fingerprint, because
+/// the importers bind to the exported items by name.
+///
+/// Each item is encoded into bytes field by field (see `encode_*` functions below)
+/// rather than by the `Debug` or `Display` text of the types, so that the fingerprint
+/// is stable across the versions of the dependencies.
+pub fn compute_interface_fingerprint(image_common_entry: &ImageCommonEntry) -> u64 {
+    let mut items: Vec<Vec<u8>> = vec![];
+
+    for (export_function_entry, function_entry) in image_common_entry
+        .export_function_entries
+        .iter()
+        .zip(image_common_entry.function_entries.iter())
+        .filter(|(export_entry, _)| export_entry.visibility == Visibility::Public)
+    {
+        let type_entry = &image_common_entry.type_entries[function_entry.type_index];
+
+        let mut item = vec![ITEM_KIND_FUNCTION];
+        encode_string(&mut item, &export_function_entry.full_name);
+        encode_operand_data_types(&mut item, &type_entry.params);
+        encode_operand_data_types(&mut item, &type_entry.results);
+        items.push(item);
+    }
+
+    // the export data entries are in the order of the data public index, i.e.
+    // read-only data, read-write data and then uninitialized data.
+    let read_only_data_count = image_common_entry.read_only_data_entries.len();
+    let read_write_data_count = image_common_entry.read_write_data_entries.len();
+
+    for (data_internal_index, export_data_entry) in image_common_entry
+        .export_data_entries
+        .iter()
+        .enumerate()
+        .filter(|(_, export_entry)| export_entry.visibility == Visibility::Public)
+    {
+        let (memory_data_type, length) = match export_data_entry.section_type {
+            DataSectionType::ReadOnly => {
+                let entry = &image_common_entry.read_only_data_entries[data_internal_index];
+                (entry.memory_data_type, entry.length)
+            }
+            DataSectionType::ReadWrite => {
+                let entry = &image_common_entry.read_write_data_entries
+                    [data_internal_index - read_only_data_count];
+                (entry.memory_data_type, entry.length)
+            }
+            DataSectionType::Uninit => {
+                let entry = &image_common_entry.uninit_data_entries
+                    [data_internal_index - read_only_data_count - read_write_data_count];
+                (entry.memory_data_type, entry.length)
+            }
+        };
+
+        let mut item = vec![ITEM_KIND_DATA];
+        encode_string(&mut item, &export_data_entry.full_name);
+        item.push(match export_data_entry.section_type {
+            DataSectionType::ReadOnly => 0,
+            DataSectionType::ReadWrite => 1,
+            DataSectionType::Uninit => 2,
+        });
+        item.push(match memory_data_type {
+            MemoryDataType::I32 => 0,
+            MemoryDataType::I64 => 1,
+            MemoryDataType::F32 => 2,
+            MemoryDataType::F64 => 3,
+            MemoryDataType::Bytes => 4,
+        });
+        item.extend_from_slice(&length.to_le_bytes());
+        items.push(item);
+    }
+
+    items.sort();
+    compute_stable_hash(&items.concat())
+}
+
+const ITEM_KIND_FUNCTION: u8 = 0;
+const ITEM_KIND_DATA: u8 = 1;
+
+/// Encodes a string as "length (u32, little-endian) + UTF-8 bytes".
+fn encode_string(bytes: &mut Vec<u8>, text: &str) {
+    bytes.extend_from_slice(&(text.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(text.as_bytes());
+}
+
+/// Encodes a list of data types as "count (u32, little-endian) + a byte for each type".
+fn encode_operand_data_types(bytes: &mut Vec<u8>, data_types: &[OperandDataType]) {
+    bytes.extend_from_slice(&(data_types.len() as u32).to_le_bytes());
+    bytes.extend(data_types.iter().map(|data_type| match data_type {
+        OperandDataType::I32 => 0,
+        OperandDataType::I64 => 1,
+        OperandDataType::F32 => 2,
+        OperandDataType::F64 => 3,
+    }));
+}
+
+/// Builds the interface fingerprints of the dependent modules (i.e. all
+/// modules except the application module), the result is returned
+/// by `dynamic_link` and should be recorded after linking.
+pub fn build_interface_fingerprint_entries(
+    // should be sorted entries
+    image_common_entries: &[ImageCommonEntry],
+) -> Vec<InterfaceFingerprintEntry> {
+    (1..image_common_entries.len())
+        .map(|module_index| {
+            InterfaceFingerprintEntry::new(
+                get_dynamic_link_module_name(image_common_entries, module_index),
+                compute_interface_fingerprint(&image_common_entries[module_index]),
+            )
+        })
+        .collect()
+}
+
+/// Checks the recorded fingerprints against the dependent modules
+/// which are going to be linked.
+///
+/// The modules which have no record (e.g. newly added) are not checked.
+pub fn check_interface_fingerprints(
+    // should be sorted entries
+    image_common_entries: &[ImageCommonEntry],
+    recorded_interface_fingerprint_entries: &[InterfaceFingerprintEntry],
+) -> Result<(), LinkerError> {
+    for module_index in 1..image_common_entries.len() {
+        check_module_interface_fingerprint(
+            image_common_entries,
+            module_index,
+            recorded_interface_fingerprint_entries,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Checks the recorded fingerprint of the specified dependent module,
+/// the application module (i.e. index 0) has no fingerprint.
+pub fn check_module_interface_fingerprint(
+    // should be sorted entries
+    image_common_entries: &[ImageCommonEntry],
+    module_index: usize,
+    recorded_interface_fingerprint_entries: &[InterfaceFingerprintEntry],
+) -> Result<(), LinkerError> {
+    if module_index == 0 {
+        return Ok(());
+    }
+
+    let module_name = get_dynamic_link_module_name(image_common_entries, module_index);
+    let recorded_opt = recorded_interface_fingerprint_entries
+        .iter()
+        .find(|item| item.module_name == module_name);
+
+    if let Some(recorded) = recorded_opt {
+        if recorded.fingerprint
+            != compute_interface_fingerprint(&image_common_entries[module_index])
+        {
+            return Err(LinkerError::new(
+                LinkErrorType::InterfaceFingerprintMismatch(module_name),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anc_image::entry::{DynamicLinkModuleEntry, ModuleLocation};
+    use anc_isa::EffectiveVersion;
+
+    use crate::{
+        dynamic_linker::{dynamic_link, sort_modules_by_dependent_deepth},
+        test_helpers::build_module,
+        ExternalSymbolNamespace, LinkErrorType, LinkerError,
+    };
+
+    use super::{build_interface_fingerprint_entries, compute_interface_fingerprint};
+
+    #[test]
+    fn test_compute_interface_fingerprint() {
+        let fingerprint = compute_interface_fingerprint(&build_module(
+            "std",
+            r#"
+pub readonly data MAGIC:i32 = 42
+
+pub fn add(left:i32, right:i32) -> i32 {
+    add_i32(
+        local_load_i32_s(left)
+        local_load_i32_s(right))
+}
+"#,
+            &[],
+            EffectiveVersion::new(0, 0, 0),
+        ));
+
+        // changing the function body and adding private items
+        // do not affect the fingerprint
+        assert_eq!(
+            compute_interface_fingerprint(&build_module(
+                "std",
+                r#"
+data count:i32 = 0
+pub readonly data MAGIC:i32 = 24
+
+fn helper() -> () {
+    nop()
+}
+
+pub fn add(left:i32, right:i32) -> i32 {
+    add_i32(
+        local_load_i32_s(right)
+        local_load_i32_s(left))
+}
+"#,
+                &[],
+                EffectiveVersion::new(0, 0, 0),
+            )),
+            fingerprint
+        );
+
+        // changing the signature of a public function
+        assert_ne!(
+            compute_interface_fingerprint(&build_module(
+                "std",
+                r#"
+pub readonly data MAGIC:i32 = 42
+
+pub fn add(left:i32) -> i32 {
+    local_load_i32_s(left)
+}
+"#,
+                &[],
+                EffectiveVersion::new(0, 0, 0),
+            )),
+            fingerprint
+        );
+
+        // changing the section of public data
+        assert_ne!(
+            compute_interface_fingerprint(&build_module(
+                "std",
+                r#"
+pub data MAGIC:i32 = 42
+
+pub fn add(left:i32, right:i32) -> i32 {
+    add_i32(
+        local_load_i32_s(left)
+        local_load_i32_s(right))
+}
+"#,
+                &[],
+                EffectiveVersion::new(0, 0, 0),
+            )),
+            fingerprint
+        );
+    }
+
+    #[test]
+    fn test_check_interface_fingerprints() {
+        let build_modules = |std_source: &str| {
+            let module_app = build_module(
+                "app",
+                r#"
+fn _start() -> () {
+    nop()
+}
+"#,
+                &[],
+                EffectiveVersion::new(0, 0, 0),
+            );
+            let module_std = build_module("std", std_source, &[], EffectiveVersion::new(0, 0, 0));
+
+            let mut image_common_entries = vec![module_app, module_std];
+            sort_modules_by_dependent_deepth(&mut image_common_entries).unwrap();
+            image_common_entries
+        };
+
+        let dynamic_link_module_entries = vec![
+            DynamicLinkModuleEntry::new("app".to_owned(), Box::new(ModuleLocation::Embed)),
+            DynamicLinkModuleEntry::new("std".to_owned(), Box::new(ModuleLocation::Runtime)),
+        ];
+
+        let image_common_entries = build_modules(
+            r#"
+pub fn add(left:i32, right:i32) -> i32 {
+    add_i32(
+        local_load_i32_s(left)
+        local_load_i32_s(right))
+}
+"#,
+        );
+
+        // the fingerprints are returned by the linking
+        let (_, recorded_entries, _) = dynamic_link(
+            &image_common_entries,
+            &dynamic_link_module_entries,
+            &[],
+            ExternalSymbolNamespace::LibraryQualified,
+            &[],
+            &[],
+        )
+        .unwrap();
+        assert_eq!(
+            recorded_entries,
+            build_interface_fingerprint_entries(&image_common_entries)
+        );
+        assert_eq!(recorded_entries.len(), 1);
+        assert_eq!(recorded_entries[0].module_name, "std");
+
+        // the interface is unchanged
+        assert!(dynamic_link(
+            &image_common_entries,
+            &dynamic_link_module_entries,
+            &[],
+            ExternalSymbolNamespace::LibraryQualified,
+            &[],
+            &recorded_entries
+        )
+        .is_ok());
+
+        // the interface is changed
+        let image_common_entries = build_modules(
+            r#"
+pub fn add(left:i32, right:i32, carry:i32) -> i32 {
+    add_i32(
+        local_load_i32_s(left)
+        local_load_i32_s(right))
+}
+"#,
+        );
+
+        assert!(matches!(
+            dynamic_link(
+                &image_common_entries,
+                &dynamic_link_module_entries,
+                &[],
+                ExternalSymbolNamespace::LibraryQualified,
+                &[],
+                &recorded_entries
+            ),
+            Err(LinkerError {
+                error_type: LinkErrorType::InterfaceFingerprintMismatch(name)
+            }) if name == "std"
+        ));
+    }
+}
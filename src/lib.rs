@@ -4,12 +4,25 @@
 // the Mozilla Public License version 2.0 and additional exceptions,
 // more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
 
+pub mod dependency_graph;
+pub mod dependency_resolver;
 pub mod dynamic_linker;
+pub mod elf_verifier;
+pub mod function_profile;
+pub mod index_cache;
+pub mod interface_fingerprint;
+pub mod module_provider;
 pub mod static_linker;
+pub mod system_library;
+
+#[cfg(test)]
+mod test_helpers;
 
 use std::fmt::Display;
 
-use anc_isa::{DataSectionType, MemoryDataType};
+use anc_image::{entry::TypeEntry, module_image::ImageType};
+use anc_isa::{DataSectionType, EffectiveVersion, MemoryDataType, OperandDataType};
+use dependency_resolver::VersionRequirement;
 
 pub const DEFAULT_ENTRY_FUNCTION_NAME: &str = "_start";
 
@@ -36,6 +49,13 @@ pub enum LinkErrorType {
     /// their versions conflict.
     DependentVersionConflict(/* module/library name */ String),
 
+    /// The version requirements of a shared module are not compatible
+    /// with each other, all requirements are listed.
+    DependentVersionRequirementConflict(
+        /* module name */ String,
+        /* requirements */ Vec<VersionRequirement>,
+    ),
+
     /// The specified function canot be found.
     FunctionNotFound(/* function name */ String),
 
@@ -67,6 +87,84 @@ pub enum LinkErrorType {
 
     ExternalFunctionTypeInconsistent(/* external function name */ String),
     ExternalDataTypeInconsistent(/* external data name */ String),
+
+    /// The entry point function (i.e. "_start" and the executable units)
+    /// should have no parameters and return at most one "i32".
+    EntryPointTypeMismatch(
+        /* function name */ String,
+        /* actual type */ TypeEntry,
+    ),
+
+    /// The unit test function should have no parameters and
+    /// return at most one "i32".
+    UnitTestTypeMismatch(
+        /* function name */ String,
+        /* actual type */ TypeEntry,
+    ),
+
+    /// The function or data is imported from a module (or the external function
+    /// comes from a library) whose dependency condition is not satisfied.
+    InactiveDependencyReferenced(
+        /* function/data name */ String,
+        /* module/library name */ String,
+    ),
+
+    /// The version of the dependent module does not satisfy the
+    /// version required by the importer.
+    ModuleVersionMismatch(
+        /* module name */ String,
+        /* importer */ String,
+        /* required version */ String,
+        /* provided version */ EffectiveVersion,
+    ),
+
+    /// The exported interface of the dependent module is changed since
+    /// the last linking, the importers should be recompiled.
+    InterfaceFingerprintMismatch(/* module name */ String),
+
+    /// The updated module does not replace an existing module in place,
+    /// or it changes the dependency order or the external functions,
+    /// a full dynamic linking is required.
+    RelinkModuleMismatch(/* module name */ String),
+
+    /// The relocation lists of the module have been stripped,
+    /// so it cannot be statically linked again.
+    StrippedModuleRelinked(/* module name */ String),
+
+    /// The image type of the input is not allowed, e.g. a shared module
+    /// is passed to the static linker, or an object file is passed to
+    /// the dynamic linker.
+    UnexpectedImageType(/* module name */ String, ImageType),
+
+    /// The line of the function profile file is not "{full_name} {count}".
+    InvalidFunctionProfile(/* line number */ usize, /* line text */ String),
+
+    /// The list of submodule base directories is not empty, but its length
+    /// differs from the number of submodules.
+    SubmoduleBaseDirectoryCountMismatch(
+        /* submodule count */ usize,
+        /* base directory count */ usize,
+    ),
+
+    /// The external functions with the same name have different types,
+    /// under the `ExternalSymbolNamespace::Global` policy.
+    ExternalFunctionConflict(/* function name */ String),
+
+    /// The file of the "Local" or "System" external library cannot be found
+    /// on the local filesystem.
+    ExternalLibraryNotFound(/* library name */ String),
+
+    /// The file of the external library is not a valid ELF shared object.
+    InvalidExternalLibrary(/* library name */ String, /* message */ String),
+
+    /// The external function is not exported by the library.
+    ExternalFunctionNotFound(
+        /* function name */ String,
+        /* library name */ String,
+    ),
+
+    /// A warning is raised when the option "warnings as errors" is enabled.
+    WarningAsError(LinkerWarning),
     // EntryPointNotFound(/* expected_entry_point_name */ String),
 }
 
@@ -85,6 +183,7 @@ impl Display for LinkerError {
             LinkErrorType::DependentNameConflict(dependency_name) =>write!(f, "Dependent \"{dependency_name}\" cannot be merged because there are different types."),
             LinkErrorType::DependentSourceConflict(dependency_name) => write!(f, "Dependent \"{dependency_name}\" cannot be merged because the sources are different."),
             LinkErrorType::DependentVersionConflict(dependency_name) => write!(f, "Dependent \"{dependency_name}\" cannot be merged because the major versions are different."),
+            LinkErrorType::DependentVersionRequirementConflict(dependency_name, requirements) => write!(f, "Dependent \"{dependency_name}\" cannot be resolved because the required versions conflict: {}.", requirements.iter().map(|item| format!("\"{}\" requires \"{}\"", item.requester, item.version)).collect::<Vec<_>>().join(", ")),
 
             LinkErrorType::FunctionNotFound(function_name) => write!(f, "The specified function \"{function_name}\" cannot be found."),
             LinkErrorType::FunctionNotExported(function_name) => write!(f, "The specified function \"{function_name}\" is not exported."),
@@ -101,6 +200,22 @@ impl Display for LinkerError {
             LinkErrorType::ExternalFunctionTypeInconsistent(external_function_name) => write!(f, "Inconsistent type of the external function \"{external_function_name}\"."),
             LinkErrorType::ExternalDataTypeInconsistent(external_data_name) => write!(f, "Inconsistent type of the external data \"{external_data_name}\"."),
 
+            LinkErrorType::EntryPointTypeMismatch(function_name, actual_type) => write!(f, "The entry point function \"{function_name}\" has the signature \"{}\", but it should be \"() -> ()\" or \"() -> (i32)\".", format_type_entry(actual_type)),
+            LinkErrorType::UnitTestTypeMismatch(function_name, actual_type) => write!(f, "The unit test function \"{function_name}\" has the signature \"{}\", but it should be \"() -> ()\" or \"() -> (i32)\".", format_type_entry(actual_type)),
+
+            LinkErrorType::InactiveDependencyReferenced(item_name, dependency_name) => write!(f, "\"{item_name}\" comes from the dependent \"{dependency_name}\" whose condition is not satisfied."),
+            LinkErrorType::ModuleVersionMismatch(module_name, importer, required_version, provided_version) => write!(f, "The dependent module \"{module_name}\" is required by \"{importer}\" to be compatible with version \"{required_version}\", but the provided version is \"{provided_version}\"."),
+            LinkErrorType::InterfaceFingerprintMismatch(module_name) => write!(f, "The exported interface of the dependent module \"{module_name}\" has changed since the last linking."),
+            LinkErrorType::RelinkModuleMismatch(module_name) => write!(f, "The module \"{module_name}\" cannot be relinked incrementally, a full linking is required."),
+            LinkErrorType::StrippedModuleRelinked(module_name) => write!(f, "The module \"{module_name}\" has been stripped and cannot be linked again."),
+            LinkErrorType::UnexpectedImageType(module_name, image_type) => write!(f, "The module \"{module_name}\" is {}, which is not allowed here.", format_image_type(image_type)),
+            LinkErrorType::ExternalFunctionConflict(function_name) => write!(f, "The external function \"{function_name}\" is declared with different types."),
+            LinkErrorType::ExternalLibraryNotFound(library_name) => write!(f, "Cannot find the file of external library \"{library_name}\"."),
+            LinkErrorType::InvalidExternalLibrary(library_name, message) => write!(f, "Failed to read external library \"{library_name}\", message: \"{message}\""),
+            LinkErrorType::ExternalFunctionNotFound(function_name, library_name) => write!(f, "The external function \"{function_name}\" is not found in library \"{library_name}\"."),
+            LinkErrorType::WarningAsError(warning) => write!(f, "Warning treated as error: {warning}"),
+            LinkErrorType::InvalidFunctionProfile(line_number, line_text) => write!(f, "Invalid function profile at line {line_number}: \"{line_text}\"."),
+            LinkErrorType::SubmoduleBaseDirectoryCountMismatch(submodule_count, base_directory_count) => write!(f, "Expected {submodule_count} submodule base directories (one for each submodule), but {base_directory_count} are given."),
 
             // LinkErrorType::EntryPointNotFound(expected_entry_point_name) => write!(f, "The entry point function \"{expected_entry_point_name}\" does not found."),
         }
@@ -108,3 +223,111 @@ impl Display for LinkerError {
 }
 
 impl std::error::Error for LinkerError {}
+
+/// Determines whether two external functions are the same.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum ExternalSymbolNamespace {
+    /// The external functions are identified by the library and the name,
+    /// i.e. the functions with the same name from different libraries
+    /// are different functions.
+    #[default]
+    LibraryQualified,
+
+    /// The external functions are identified by the name only, like
+    /// C/ELF programs, the functions with the same name are merged into the
+    /// first declared one, and they should have the same type.
+    Global,
+}
+
+/// The situations which do not fail the linking but should be surfaced,
+/// returned next to the successful result of `static_link` and `dynamic_link`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum LinkerWarning {
+    /// The "Share" dependency declared by the requester is upgraded to a newer
+    /// compatible version, i.e. the one required by another submodule when
+    /// static linking, or the one provided when dynamic linking.
+    DependencyUpgraded(
+        /* module name */ String,
+        /* requester */ String,
+        /* required version */ String,
+        /* resolved version */ String,
+    ),
+
+    /// Nothing is imported from the module.
+    UnusedImportModule(/* module name */ String),
+
+    /// No external function comes from the library.
+    UnusedExternalLibrary(/* library name */ String),
+
+    /// The exported function or data is demoted to private by an export filter.
+    ExportDemoted(/* full name */ String),
+
+    /// The external functions with the same name from different libraries
+    /// are merged into the first declared one, it only happens in the
+    /// `ExternalSymbolNamespace::Global` namespace.
+    ExternalFunctionMerged(
+        /* function name */ String,
+        /* library of the kept function */ String,
+        /* library of the merged function */ String,
+    ),
+
+    /// The linking result can not be written to the index cache,
+    /// the linking itself is not affected.
+    IndexCacheNotWritten(/* path */ String, /* message */ String),
+}
+
+impl Display for LinkerWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LinkerWarning::DependencyUpgraded(module_name, requester, required_version, resolved_version) => write!(f, "The dependent module \"{module_name}\" required by \"{requester}\" is upgraded from version \"{required_version}\" to \"{resolved_version}\"."),
+            LinkerWarning::UnusedImportModule(module_name) => write!(f, "Nothing is imported from the dependent module \"{module_name}\"."),
+            LinkerWarning::UnusedExternalLibrary(library_name) => write!(f, "No function is used from the external library \"{library_name}\"."),
+            LinkerWarning::ExportDemoted(full_name) => write!(f, "\"{full_name}\" is no longer exported."),
+            LinkerWarning::ExternalFunctionMerged(function_name, kept_library_name, merged_library_name) => write!(f, "The external function \"{function_name}\" from the library \"{merged_library_name}\" is merged into the one from the library \"{kept_library_name}\"."),
+            LinkerWarning::IndexCacheNotWritten(path, message) => write!(f, "Cannot write the index cache \"{path}\": {message}."),
+        }
+    }
+}
+
+/// Computes the FNV-1a (64-bit) hash of the given bytes.
+///
+/// `DefaultHasher` is not used because its output is not guaranteed to be
+/// stable across Rust releases, while the hashes are persisted.
+pub(crate) fn compute_stable_hash(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+fn format_image_type(image_type: &ImageType) -> &'static str {
+    match image_type {
+        ImageType::Application => "an application",
+        ImageType::SharedModule => "a shared module",
+        ImageType::ObjectFile => "an object file",
+    }
+}
+
+/// Format the type as text, e.g. "(i32, i64) -> (f32)".
+pub(crate) fn format_type_entry(type_entry: &TypeEntry) -> String {
+    let format_data_types = |data_types: &[OperandDataType]| {
+        data_types
+            .iter()
+            .map(|item| match item {
+                OperandDataType::I32 => "i32",
+                OperandDataType::I64 => "i64",
+                OperandDataType::F32 => "f32",
+                OperandDataType::F64 => "f64",
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    format!(
+        "({}) -> ({})",
+        format_data_types(&type_entry.params),
+        format_data_types(&type_entry.results)
+    )
+}
@@ -0,0 +1,740 @@
+// Copyright (c) 2026 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+use std::{
+    collections::VecDeque,
+    path::{Path, PathBuf},
+};
+
+use anc_image::{
+    entry::{
+        DynamicLinkModuleEntry, ImageCommonEntry, ImageIndexEntry, ImportModuleEntry,
+        ModuleLocation,
+    },
+    entry_reader::read_object_file,
+};
+use anc_isa::{EffectiveVersion, ModuleDependency, VersionCompatibility};
+
+use crate::{
+    dependency_resolver::{is_module_dependency_active, DependencyVersionResolver},
+    dynamic_linker::{
+        calculate_active_module_deepths, dynamic_link, get_dynamic_link_module_name,
+        resolve_import_module_index,
+    },
+    interface_fingerprint::InterfaceFingerprintEntry,
+    ExternalSymbolNamespace, LinkErrorType, LinkerError, LinkerWarning,
+};
+
+const MODULE_FILE_EXTENSION: &str = "anc";
+
+/// A module loaded by `ModuleProvider`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct LoadedModule {
+    pub image_common_entry: ImageCommonEntry,
+
+    /// The location which is recorded in the `DynamicLinkModuleEntry`.
+    pub module_location: ModuleLocation,
+}
+
+/// Loads the modules which are required by the application.
+pub trait ModuleProvider {
+    /// Loads the module which the import entry refers to.
+    ///
+    /// For "Share" dependencies, the provider should return the newest
+    /// version which is compatible with the required version, e.g. "json 1.4.1"
+    /// for "json 1.2.0", the version is checked again when linking.
+    fn load_module(
+        &self,
+        import_module_entry: &ImportModuleEntry,
+    ) -> Result<LoadedModule, LinkerError>;
+}
+
+#[derive(Debug)]
+pub struct ApplicationLinkOutput {
+    /// The application and all its dependent modules, sorted by
+    /// dependent deepth, i.e. the first one is the application.
+    pub image_common_entries: Vec<ImageCommonEntry>,
+
+    pub image_index_entry: ImageIndexEntry,
+
+    /// The interface fingerprints of the dependent modules, see `dynamic_link`.
+    pub interface_fingerprint_entries: Vec<InterfaceFingerprintEntry>,
+
+    pub warnings: Vec<LinkerWarning>,
+}
+
+/// Loads all dependent modules of the application transitively
+/// through the provider, and then links them.
+///
+/// The "Share" dependencies of one module are resolved together, i.e. the newest
+/// version among the compatible requirements is loaded, e.g. "json 1.4.0" is loaded
+/// for both "json 1.2.0" and "json 1.4.0", while "json 2.0.0" is loaded side by side.
+/// Since a newer version may import different modules, the loading is repeated
+/// until no more module is required.
+///
+/// The dependencies whose condition is not satisfied are not loaded.
+pub fn load_and_link_application(
+    module_provider: &dyn ModuleProvider,
+    application_entry: ImageCommonEntry,
+    active_flags: &[&str],
+    external_symbol_namespace: ExternalSymbolNamespace,
+    system_library_search_directories: &[PathBuf],
+    recorded_interface_fingerprint_entries: &[InterfaceFingerprintEntry],
+) -> Result<ApplicationLinkOutput, LinkerError> {
+    let mut image_common_entries = vec![application_entry];
+    let mut module_locations = vec![ModuleLocation::Embed];
+
+    loop {
+        let requirements = find_reachable_module_indices(&image_common_entries, active_flags)
+            .into_iter()
+            .flat_map(|module_index| {
+                let image_common_entry = &image_common_entries[module_index];
+                image_common_entry
+                    .import_module_entries
+                    .iter()
+                    .filter(|item| is_active_import_module_entry(item, active_flags))
+                    .map(|item| (image_common_entry.name.to_owned(), item.clone()))
+            })
+            .collect::<Vec<_>>();
+
+        let mut is_changed = false;
+
+        for import_module_entry in resolve_module_requirements(&requirements)? {
+            if is_import_module_provided(&image_common_entries, &import_module_entry) {
+                continue;
+            }
+
+            let loaded_module = module_provider.load_module(&import_module_entry)?;
+            image_common_entries.push(loaded_module.image_common_entry);
+            module_locations.push(loaded_module.module_location);
+
+            // stop loading the same module again and again
+            if !is_import_module_provided(&image_common_entries, &import_module_entry) {
+                return Err(LinkerError::new(LinkErrorType::CannotLoadMoudle(
+                    import_module_entry.name.to_owned(),
+                    "The provided module does not satisfy the requirement.".to_owned(),
+                )));
+            }
+
+            is_changed = true;
+        }
+
+        if !is_changed {
+            break;
+        }
+    }
+
+    // the modules which are replaced by newer versions are dropped.
+    let reachable_module_indices =
+        find_reachable_module_indices(&image_common_entries, active_flags);
+    let mut module_item_opts = image_common_entries
+        .into_iter()
+        .zip(module_locations)
+        .map(Some)
+        .collect::<Vec<_>>();
+    let (image_common_entries, module_locations): (Vec<_>, Vec<_>) = reachable_module_indices
+        .iter()
+        .map(|module_index| module_item_opts[*module_index].take().unwrap())
+        .unzip();
+
+    // sort the modules by deepth, see `sort_modules_by_dependent_deepth`.
+    let module_deepths =
+        calculate_active_module_deepths(&image_common_entries, Some(active_flags))?;
+    let mut module_items = image_common_entries
+        .into_iter()
+        .zip(module_locations)
+        .zip(module_deepths)
+        .collect::<Vec<_>>();
+    module_items.sort_by_key(|(_, deepth)| *deepth);
+    let (image_common_entries, module_locations): (Vec<_>, Vec<_>) =
+        module_items.into_iter().map(|(item, _)| item).unzip();
+
+    let dynamic_link_module_entries = module_locations
+        .into_iter()
+        .enumerate()
+        .map(|(module_index, module_location)| {
+            DynamicLinkModuleEntry::new(
+                get_dynamic_link_module_name(&image_common_entries, module_index),
+                Box::new(module_location),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    // the upgraded dependencies are reported by the dynamic linker.
+    let (image_index_entry, interface_fingerprint_entries, warnings) = dynamic_link(
+        &image_common_entries,
+        &dynamic_link_module_entries,
+        active_flags,
+        external_symbol_namespace,
+        system_library_search_directories,
+        recorded_interface_fingerprint_entries,
+    )?;
+
+    Ok(ApplicationLinkOutput {
+        image_common_entries,
+        image_index_entry,
+        interface_fingerprint_entries,
+        warnings,
+    })
+}
+
+/// The self reference item and the dependencies whose condition
+/// is not satisfied are not loaded.
+fn is_active_import_module_entry(
+    import_module_entry: &ImportModuleEntry,
+    active_flags: &[&str],
+) -> bool {
+    import_module_entry != &ImportModuleEntry::self_reference_entry()
+        && is_module_dependency_active(&import_module_entry.module_dependency, active_flags)
+}
+
+/// Checks whether the import entry is bound (see `resolve_import_module_index`) to
+/// a loaded module, and for "Share" dependencies, the version is compatible.
+fn is_import_module_provided(
+    image_common_entries: &[ImageCommonEntry],
+    import_module_entry: &ImportModuleEntry,
+) -> bool {
+    resolve_import_module_index(image_common_entries, import_module_entry).is_some_and(
+        |module_index| match import_module_entry.module_dependency.as_ref() {
+            ModuleDependency::Share(share) => matches!(
+                EffectiveVersion::from_str(&share.version)
+                    .compatible(&image_common_entries[module_index].version),
+                VersionCompatibility::Equals | VersionCompatibility::LessThan
+            ),
+            _ => true,
+        },
+    )
+}
+
+/// Finds the modules which are reachable from the application, the import
+/// edges are bound by `resolve_import_module_index`, and the edges whose
+/// target is not loaded yet are not followed.
+///
+/// The result is in the order in which the modules are found.
+fn find_reachable_module_indices(
+    image_common_entries: &[ImageCommonEntry],
+    active_flags: &[&str],
+) -> Vec<usize> {
+    let mut module_indices = vec![0];
+    let mut pending_module_indices = VecDeque::from([0]);
+
+    while let Some(parent_module_index) = pending_module_indices.pop_front() {
+        for import_module_entry in &image_common_entries[parent_module_index].import_module_entries
+        {
+            if !is_active_import_module_entry(import_module_entry, active_flags) {
+                continue;
+            }
+
+            if let Some(module_index) =
+                resolve_import_module_index(image_common_entries, import_module_entry)
+            {
+                if !module_indices.contains(&module_index) {
+                    module_indices.push(module_index);
+                    pending_module_indices.push_back(module_index);
+                }
+            }
+        }
+    }
+
+    module_indices
+}
+
+/// Picks one import entry for each group of compatible requirements.
+///
+/// For "Share" dependencies, the versions are resolved by `DependencyVersionResolver`
+/// with side by side versions, i.e. the newest one of each group of compatible
+/// requirements is picked, for the other dependencies all requirements of
+/// a module should be identical.
+fn resolve_module_requirements(
+    requirements: &[(/* requester */ String, ImportModuleEntry)],
+) -> Result<Vec<ImportModuleEntry>, LinkerError> {
+    let mut module_names: Vec<&str> = vec![];
+    for (_, import_module_entry) in requirements {
+        if !module_names.contains(&import_module_entry.name.as_str()) {
+            module_names.push(&import_module_entry.name);
+        }
+    }
+
+    let mut resolved_import_module_entries = vec![];
+
+    for module_name in module_names {
+        let module_requirements = requirements
+            .iter()
+            .filter(|(_, item)| item.name == module_name)
+            .collect::<Vec<_>>();
+
+        let share_count = module_requirements
+            .iter()
+            .filter(|(_, item)| {
+                matches!(item.module_dependency.as_ref(), ModuleDependency::Share(_))
+            })
+            .count();
+
+        if share_count == 0 {
+            let first_import_module_entry = &module_requirements[0].1;
+            if module_requirements
+                .iter()
+                .any(|(_, item)| item != first_import_module_entry)
+            {
+                return Err(LinkerError::new(LinkErrorType::DependentSourceConflict(
+                    module_name.to_owned(),
+                )));
+            }
+
+            resolved_import_module_entries.push(first_import_module_entry.clone());
+            continue;
+        }
+
+        if share_count != module_requirements.len() {
+            return Err(LinkerError::new(LinkErrorType::DependentNameConflict(
+                module_name.to_owned(),
+            )));
+        }
+
+        let mut version_resolver = DependencyVersionResolver::new().side_by_side_versions(true);
+        for (requester, import_module_entry) in &module_requirements {
+            if let ModuleDependency::Share(share) = import_module_entry.module_dependency.as_ref() {
+                version_resolver.add_requirement(module_name, requester, &share.version);
+            }
+        }
+
+        for resolved_version in version_resolver.resolve()? {
+            // the first entry which requires the resolved version
+            let (_, import_module_entry) = module_requirements
+                .iter()
+                .find(|(_, item)| {
+                    matches!(
+                        item.module_dependency.as_ref(),
+                        ModuleDependency::Share(share) if share.version == resolved_version.version
+                    )
+                })
+                .unwrap();
+            resolved_import_module_entries.push(import_module_entry.clone());
+        }
+    }
+
+    Ok(resolved_import_module_entries)
+}
+
+/// Loads the shared module files (i.e. the images without the index sections,
+/// see `read_object_file`) from a directory, it is mainly for testing.
+///
+/// The file of a module is named by the module name and version,
+/// e.g. "json@1.4.1.anc", or only the module name, e.g. "json.anc",
+/// the latter is used when no versioned file is compatible.
+///
+/// The location of the modules is `ModuleLocation::Runtime`, i.e. the
+/// runtime is expected to locate the modules by itself.
+pub struct FileSystemModuleProvider {
+    module_directory: PathBuf,
+}
+
+impl FileSystemModuleProvider {
+    pub fn new(module_directory: &Path) -> Self {
+        Self {
+            module_directory: module_directory.to_path_buf(),
+        }
+    }
+
+    /// Finds the file of the module, the error of reading the module
+    /// directory is reported instead of being treated as no file.
+    fn find_module_file(
+        &self,
+        import_module_entry: &ImportModuleEntry,
+    ) -> Result<Option<PathBuf>, LinkerError> {
+        let module_name = &import_module_entry.name;
+
+        let read_directory_error = |error: std::io::Error| {
+            LinkerError::new(LinkErrorType::CannotLoadMoudle(
+                module_name.to_owned(),
+                format!(
+                    "Failed to read the module directory \"{}\": {}",
+                    self.module_directory.display(),
+                    error
+                ),
+            ))
+        };
+
+        if let ModuleDependency::Share(share) = import_module_entry.module_dependency.as_ref() {
+            let required_version = EffectiveVersion::from_str(&share.version);
+            let file_name_prefix = format!("{}@", module_name);
+            let file_name_suffix = format!(".{}", MODULE_FILE_EXTENSION);
+
+            let mut selected_file_opt: Option<(PathBuf, EffectiveVersion)> = None;
+
+            for dir_entry_result in
+                std::fs::read_dir(&self.module_directory).map_err(read_directory_error)?
+            {
+                let dir_entry = dir_entry_result.map_err(read_directory_error)?;
+                let file_name = dir_entry.file_name().to_string_lossy().to_string();
+                let Some(version_text) = file_name
+                    .strip_prefix(&file_name_prefix)
+                    .and_then(|item| item.strip_suffix(&file_name_suffix))
+                else {
+                    continue;
+                };
+
+                let candidate_version = EffectiveVersion::from_str(version_text);
+                if !matches!(
+                    required_version.compatible(&candidate_version),
+                    VersionCompatibility::Equals | VersionCompatibility::LessThan
+                ) {
+                    continue;
+                }
+
+                // pick the newest compatible version
+                if selected_file_opt
+                    .as_ref()
+                    .is_none_or(|(_, selected_version)| {
+                        candidate_version.compatible(selected_version)
+                            == VersionCompatibility::GreaterThan
+                    })
+                {
+                    selected_file_opt = Some((dir_entry.path(), candidate_version));
+                }
+            }
+
+            if let Some((file_path, _)) = selected_file_opt {
+                return Ok(Some(file_path));
+            }
+        }
+
+        let file_path = self
+            .module_directory
+            .join(format!("{}.{}", module_name, MODULE_FILE_EXTENSION));
+        Ok(file_path.is_file().then_some(file_path))
+    }
+}
+
+impl ModuleProvider for FileSystemModuleProvider {
+    fn load_module(
+        &self,
+        import_module_entry: &ImportModuleEntry,
+    ) -> Result<LoadedModule, LinkerError> {
+        let module_name = &import_module_entry.name;
+
+        let file_path = self.find_module_file(import_module_entry)?.ok_or_else(|| {
+            LinkerError::new(LinkErrorType::CannotLoadMoudle(
+                module_name.to_owned(),
+                format!(
+                    "The module file cannot be found in \"{}\".",
+                    self.module_directory.display()
+                ),
+            ))
+        })?;
+
+        let binary = std::fs::read(&file_path).map_err(|error| {
+            LinkerError::new(LinkErrorType::CannotLoadMoudle(
+                module_name.to_owned(),
+                format!(
+                    "Failed to read the module file \"{}\": {}",
+                    file_path.display(),
+                    error
+                ),
+            ))
+        })?;
+
+        let image_common_entry = read_object_file(&binary).map_err(|error| {
+            LinkerError::new(LinkErrorType::CannotLoadMoudle(
+                module_name.to_owned(),
+                format!(
+                    "Failed to parse the module file \"{}\": {}",
+                    file_path.display(),
+                    error
+                ),
+            ))
+        })?;
+
+        Ok(LoadedModule {
+            image_common_entry,
+            module_location: ModuleLocation::Runtime,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use anc_image::entry::{
+        DynamicLinkModuleEntry, ImageCommonEntry, ImportModuleEntry, ModuleLocation,
+    };
+    use anc_isa::{EffectiveVersion, ModuleDependency, VersionCompatibility};
+
+    use crate::{
+        test_helpers::{build_module, make_runtime_dependency, make_share_dependency},
+        ExternalSymbolNamespace, LinkErrorType, LinkerError, LinkerWarning,
+    };
+
+    use super::{
+        load_and_link_application, resolve_module_requirements, FileSystemModuleProvider,
+        LoadedModule, ModuleProvider,
+    };
+
+    /// Provides the newest compatible module among the given modules.
+    struct MemoryModuleProvider {
+        module_entries: Vec<ImageCommonEntry>,
+    }
+
+    impl ModuleProvider for MemoryModuleProvider {
+        fn load_module(
+            &self,
+            import_module_entry: &ImportModuleEntry,
+        ) -> Result<LoadedModule, LinkerError> {
+            self.module_entries
+                .iter()
+                .filter(|item| item.name == import_module_entry.name)
+                .filter(
+                    |item| match import_module_entry.module_dependency.as_ref() {
+                        ModuleDependency::Share(share) => matches!(
+                            EffectiveVersion::from_str(&share.version).compatible(&item.version),
+                            VersionCompatibility::Equals | VersionCompatibility::LessThan
+                        ),
+                        _ => true,
+                    },
+                )
+                .max_by_key(|item| (item.version.major, item.version.minor, item.version.patch))
+                .map(|item| LoadedModule {
+                    image_common_entry: item.clone(),
+                    module_location: ModuleLocation::Runtime,
+                })
+                .ok_or_else(|| {
+                    LinkerError::new(LinkErrorType::CannotLoadMoudle(
+                        import_module_entry.name.to_owned(),
+                        "Not found.".to_owned(),
+                    ))
+                })
+        }
+    }
+
+    #[test]
+    fn test_resolve_module_requirements() {
+        let requirements = vec![
+            ("app".to_owned(), make_share_dependency("json", "1.2.0")),
+            ("app".to_owned(), make_share_dependency("util", "1.0.0")),
+            ("network".to_owned(), make_share_dependency("json", "1.4.0")),
+            ("network".to_owned(), make_share_dependency("json", "2.0.0")),
+            ("network".to_owned(), make_share_dependency("util", "1.0.0")),
+        ];
+
+        assert_eq!(
+            resolve_module_requirements(&requirements).unwrap(),
+            vec![
+                make_share_dependency("json", "1.4.0"),
+                make_share_dependency("json", "2.0.0"),
+                make_share_dependency("util", "1.0.0"),
+            ]
+        );
+
+        // mixed dependency types
+        assert!(matches!(
+            resolve_module_requirements(&[
+                ("app".to_owned(), make_share_dependency("json", "1.2.0")),
+                ("network".to_owned(), make_runtime_dependency("json")),
+            ]),
+            Err(LinkerError {
+                error_type: LinkErrorType::DependentNameConflict(name)
+            }) if name == "json"
+        ));
+    }
+
+    #[test]
+    fn test_load_and_link_application() {
+        let module_app = build_module(
+            "app",
+            r#"
+import fn json::ver() -> i32
+import fn util::get() -> i32
+
+fn _start() -> i32 {
+    add_i32(
+        call(ver)
+        call(get))
+}
+"#,
+            &[
+                make_share_dependency("json", "1.2.0"),
+                make_share_dependency("util", "1.0.0"),
+            ],
+            EffectiveVersion::new(0, 0, 0),
+        );
+
+        let module_util = build_module(
+            "util",
+            r#"
+import fn json::ver() -> i32
+
+pub fn get() -> i32 {
+    call(ver)
+}
+"#,
+            &[make_share_dependency("json", "2.0.0")],
+            EffectiveVersion::new(1, 0, 0),
+        );
+
+        let build_json_module = |version: EffectiveVersion| {
+            build_module(
+                "json",
+                r#"
+pub fn ver() -> i32 {
+    imm_i32(1)
+}
+"#,
+                &[],
+                version,
+            )
+        };
+
+        let module_provider = MemoryModuleProvider {
+            module_entries: vec![
+                build_json_module(EffectiveVersion::new(1, 2, 0)),
+                build_json_module(EffectiveVersion::new(2, 0, 0)),
+                module_util,
+                build_json_module(EffectiveVersion::new(1, 4, 1)),
+            ],
+        };
+
+        let output = load_and_link_application(
+            &module_provider,
+            module_app,
+            &[],
+            ExternalSymbolNamespace::LibraryQualified,
+            &[],
+            &[],
+        )
+        .unwrap();
+
+        assert_eq!(
+            output
+                .image_common_entries
+                .iter()
+                .map(|item| format!("{}@{}", item.name, item.version))
+                .collect::<Vec<_>>(),
+            vec!["app@0.0.0", "json@1.4.1", "util@1.0.0", "json@2.0.0"]
+        );
+
+        assert_eq!(
+            output.warnings,
+            vec![LinkerWarning::DependencyUpgraded(
+                "json".to_owned(),
+                "app".to_owned(),
+                "1.2.0".to_owned(),
+                "1.4.1".to_owned()
+            )]
+        );
+
+        assert_eq!(
+            output.image_index_entry.dynamic_link_module_entries,
+            vec![
+                DynamicLinkModuleEntry::new("app".to_owned(), Box::new(ModuleLocation::Embed)),
+                DynamicLinkModuleEntry::new(
+                    "json@1.4.1".to_owned(),
+                    Box::new(ModuleLocation::Runtime)
+                ),
+                DynamicLinkModuleEntry::new("util".to_owned(), Box::new(ModuleLocation::Runtime)),
+                DynamicLinkModuleEntry::new(
+                    "json@2.0.0".to_owned(),
+                    Box::new(ModuleLocation::Runtime)
+                ),
+            ]
+        );
+
+        // module not found
+        let module_app = build_module(
+            "app",
+            r#"
+import fn math::inc(i32) -> i32
+
+fn _start() -> i32 {
+    call(inc, imm_i32(1))
+}
+"#,
+            &[make_share_dependency("math", "1.0.0")],
+            EffectiveVersion::new(0, 0, 0),
+        );
+
+        assert!(matches!(
+            load_and_link_application(
+                &module_provider,
+                module_app,
+                &[],
+                ExternalSymbolNamespace::LibraryQualified,
+                &[],
+                &[],
+            ),
+            Err(LinkerError {
+                error_type: LinkErrorType::CannotLoadMoudle(name, _)
+            }) if name == "math"
+        ));
+    }
+
+    #[test]
+    fn test_file_system_module_provider_find_module_file() {
+        let module_directory =
+            std::env::temp_dir().join(format!("anc-linker-module-provider-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&module_directory);
+        std::fs::create_dir_all(&module_directory).unwrap();
+
+        for file_name in [
+            "json@1.2.0.anc",
+            "json@1.4.1.anc",
+            "json@2.0.0.anc",
+            "util.anc",
+        ] {
+            std::fs::write(module_directory.join(file_name), []).unwrap();
+        }
+
+        let module_provider = FileSystemModuleProvider::new(&module_directory);
+        let find_module_file_name = |import_module_entry: &ImportModuleEntry| {
+            module_provider
+                .find_module_file(import_module_entry)
+                .unwrap()
+                .map(|item| item.file_name().unwrap().to_string_lossy().to_string())
+        };
+
+        assert_eq!(
+            find_module_file_name(&make_share_dependency("json", "1.2.0")),
+            Some("json@1.4.1.anc".to_owned())
+        );
+        assert_eq!(
+            find_module_file_name(&make_share_dependency("json", "2.0.0")),
+            Some("json@2.0.0.anc".to_owned())
+        );
+        assert_eq!(
+            find_module_file_name(&make_share_dependency("json", "3.0.0")),
+            None
+        );
+        assert_eq!(
+            find_module_file_name(&make_share_dependency("util", "1.0.0")),
+            Some("util.anc".to_owned())
+        );
+        assert_eq!(
+            find_module_file_name(&ImportModuleEntry::new(
+                "util".to_owned(),
+                Box::new(ModuleDependency::Runtime)
+            )),
+            Some("util.anc".to_owned())
+        );
+
+        // the file is not a valid image
+        assert!(matches!(
+            module_provider.load_module(&make_share_dependency("json", "1.2.0")),
+            Err(LinkerError {
+                error_type: LinkErrorType::CannotLoadMoudle(name, _)
+            }) if name == "json"
+        ));
+
+        std::fs::remove_dir_all(&module_directory).unwrap();
+
+        // the module directory cannot be read
+        assert!(matches!(
+            module_provider.find_module_file(&make_share_dependency("json", "1.2.0")),
+            Err(LinkerError {
+                error_type: LinkErrorType::CannotLoadMoudle(name, message)
+            }) if name == "json" && message.starts_with("Failed to read the module directory")
+        ));
+    }
+}
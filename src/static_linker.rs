@@ -4,20 +4,36 @@
 // the Mozilla Public License version 2.0 and additional exceptions,
 // more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
 
+use std::path::{Path, PathBuf};
+
 use anc_image::{
     entry::{
         ExportDataEntry, ExportFunctionEntry, ExternalFunctionEntry, ExternalLibraryEntry,
         FunctionEntry, ImageCommonEntry, ImportDataEntry, ImportFunctionEntry, ImportModuleEntry,
-        InitedDataEntry, LocalVariableListEntry, RelocateListEntry, TypeEntry, UninitDataEntry,
+        InitedDataEntry, LocalVariableListEntry, RelocateEntry, RelocateListEntry, TypeEntry,
+        UninitDataEntry,
     },
-    module_image::{ImageType, RelocateType},
+    module_image::{ImageType, RelocateType, Visibility},
 };
 use anc_isa::{
-    DataSectionType, EffectiveVersion, ExternalLibraryDependency, ModuleDependency,
-    VersionCompatibility,
+    opcode::Opcode, DataSectionType, EffectiveVersion, ExternalLibraryDependency, ModuleDependency,
+    OperandDataType, VersionCompatibility,
 };
 
-use crate::{LinkErrorType, LinkerError};
+use crate::{
+    dependency_resolver::{
+        is_external_library_dependency_active, is_module_dependency_active,
+        normalize_local_dependency_path, DependencyVersionResolver,
+    },
+    elf_verifier::verify_external_functions,
+    function_profile::{calculate_function_order, FunctionProfileEntry},
+    system_library::{
+        compare_system_library_versions, get_default_library_search_directories,
+        parse_system_library_value,
+    },
+    ExternalSymbolNamespace, LinkErrorType, LinkerError, LinkerWarning,
+    DEFAULT_ENTRY_FUNCTION_NAME,
+};
 
 /// Map the index in a module to the new index in the merged module
 ///
@@ -32,6 +48,10 @@ use crate::{LinkErrorType, LinkerError};
 /// | N                               | X                          |
 pub type RemapIndices = Vec<usize>;
 
+/// Same as `RemapIndices`, but the entries which are dropped (i.e. the
+/// dependencies whose condition is not satisfied) are mapped to `None`.
+pub type DroppableRemapIndices = Vec<Option<usize>>;
+
 pub struct RemapTable<'a> {
     pub type_remap_indices: &'a RemapIndices,
     pub data_public_remap_indices: &'a RemapIndices,
@@ -40,15 +60,317 @@ pub struct RemapTable<'a> {
     pub external_function_remap_indices: &'a RemapIndices,
 }
 
+/// The options of `StaticLinker`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct StaticLinkOptions {
+    /// The image type of the output, i.e. `ObjectFile`, `SharedModule`
+    /// or `Application`. When the output is not an object file, all internal
+    /// function and data references (i.e. `import fn/data module::...`)
+    /// need to be resolved.
+    pub image_type: ImageType,
+
+    /// Removes the internal functions which are not reachable from the
+    /// public functions and the entry points, see `remove_unused_functions`.
+    /// It only applies when the output is not an object file.
+    pub remove_unused_functions: bool,
+
+    /// Removes the relocation lists, unused type entries and unused local
+    /// variable lists from the output, it only applies when the output is
+    /// not an object file since such module is never relinked.
+    pub strip: bool,
+
+    /// Inlines the trivial getters and forwarding wrappers into their callers,
+    /// see `inline_trivial_functions` for details.
+    pub inline_functions: bool,
+
+    /// Reorders the data entries within each section by alignment and size
+    /// to minimize the padding, see `layout_data_entries` for details.
+    pub optimize_data_layout: bool,
+
+    /// The call counts of functions collected by the VM profiler (see
+    /// `parse_function_profile`), the hot functions are placed at the start
+    /// of the function entries. An empty list means no reordering.
+    pub function_profile_entries: Vec<FunctionProfileEntry>,
+
+    /// Determines whether two external functions are the same.
+    pub external_symbol_namespace: ExternalSymbolNamespace,
+
+    /// The public functions and data whose full name does not match any
+    /// filter are demoted to private, a filter is a full name which can
+    /// contain the wildcard "*", e.g. "hello::api::*".
+    /// An empty list means all public items are kept.
+    pub export_filters: Vec<String>,
+
+    /// The active feature or platform flags, used to evaluate
+    /// the conditions of dependencies.
+    /// The dependencies whose condition is not satisfied are dropped.
+    pub active_flags: Vec<String>,
+
+    /// The base directory of each submodule (relative to the output module),
+    /// used to normalize the paths of "Local" dependencies.
+    /// An empty list means that all submodules are in the directory
+    /// of the output module, otherwise the list should have one directory
+    /// for each submodule.
+    pub submodule_base_directories: Vec<PathBuf>,
+
+    /// Fails the linking on the first warning.
+    pub warnings_as_errors: bool,
+
+    /// Includes the data section sizes in the output. When `optimize_data_layout`
+    /// is enabled, the sizes are the ones before and after reordering, otherwise
+    /// the saving of reordering is estimated, see `build_data_layout_report`.
+    pub report_data_layout: bool,
+
+    /// Verifies that the external functions are exported by the "Local" and
+    /// "System" libraries on the local filesystem, see `verify_external_functions`.
+    pub verify_external_functions: bool,
+
+    /// The directories for finding the "System" libraries when verifying the
+    /// external functions, an empty list means the default directories,
+    /// see `get_default_library_search_directories`.
+    pub system_library_search_directories: Vec<PathBuf>,
+}
+
+impl Default for StaticLinkOptions {
+    fn default() -> Self {
+        Self {
+            image_type: ImageType::ObjectFile,
+            remove_unused_functions: false,
+            strip: false,
+            inline_functions: false,
+            optimize_data_layout: false,
+            function_profile_entries: vec![],
+            external_symbol_namespace: ExternalSymbolNamespace::default(),
+            export_filters: vec![],
+            active_flags: vec![],
+            submodule_base_directories: vec![],
+            warnings_as_errors: false,
+            report_data_layout: false,
+            verify_external_functions: false,
+            system_library_search_directories: vec![],
+        }
+    }
+}
+
+/// The result of `StaticLinker::link`.
+#[derive(Debug, PartialEq)]
+pub struct StaticLinkOutput {
+    pub image_common_entry: ImageCommonEntry,
+    pub warnings: Vec<LinkerWarning>,
+
+    /// The full names of the functions removed by `remove_unused_functions`.
+    pub removed_function_names: Vec<String>,
+
+    /// Present when `report_data_layout` is enabled.
+    pub data_layout_report_opt: Option<DataLayoutReport>,
+}
+
+/// Links the submodules (object files) into an object file, a shared module
+/// or an application module, e.g.
+///
+/// ```text
+/// let output = StaticLinker::new("hello", &version, &submodule_entries)
+///     .image_type(ImageType::SharedModule)
+///     .strip(true)
+///     .export_filters(&["hello::api::*"])
+///     .link()?;
+/// ```
+pub struct StaticLinker<'a> {
+    target_module_name: String,
+    target_module_version: EffectiveVersion,
+    submodule_entries: &'a [ImageCommonEntry],
+    options: StaticLinkOptions,
+}
+
+impl<'a> StaticLinker<'a> {
+    pub fn new(
+        target_module_name: &str,
+        target_module_version: &EffectiveVersion,
+        submodule_entries: &'a [ImageCommonEntry],
+    ) -> Self {
+        Self {
+            target_module_name: target_module_name.to_owned(),
+            target_module_version: *target_module_version,
+            submodule_entries,
+            options: StaticLinkOptions::default(),
+        }
+    }
+
+    pub fn options(mut self, options: StaticLinkOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn image_type(mut self, image_type: ImageType) -> Self {
+        self.options.image_type = image_type;
+        self
+    }
+
+    pub fn remove_unused_functions(mut self, remove_unused_functions: bool) -> Self {
+        self.options.remove_unused_functions = remove_unused_functions;
+        self
+    }
+
+    pub fn strip(mut self, strip: bool) -> Self {
+        self.options.strip = strip;
+        self
+    }
+
+    pub fn inline_functions(mut self, inline_functions: bool) -> Self {
+        self.options.inline_functions = inline_functions;
+        self
+    }
+
+    pub fn optimize_data_layout(mut self, optimize_data_layout: bool) -> Self {
+        self.options.optimize_data_layout = optimize_data_layout;
+        self
+    }
+
+    pub fn function_profile_entries(
+        mut self,
+        function_profile_entries: &[FunctionProfileEntry],
+    ) -> Self {
+        self.options.function_profile_entries = function_profile_entries.to_vec();
+        self
+    }
+
+    pub fn external_symbol_namespace(
+        mut self,
+        external_symbol_namespace: ExternalSymbolNamespace,
+    ) -> Self {
+        self.options.external_symbol_namespace = external_symbol_namespace;
+        self
+    }
+
+    pub fn export_filters(mut self, export_filters: &[&str]) -> Self {
+        self.options.export_filters = export_filters.iter().map(|item| item.to_string()).collect();
+        self
+    }
+
+    pub fn active_flags(mut self, active_flags: &[&str]) -> Self {
+        self.options.active_flags = active_flags.iter().map(|item| item.to_string()).collect();
+        self
+    }
+
+    pub fn submodule_base_directories(mut self, submodule_base_directories: &[PathBuf]) -> Self {
+        self.options.submodule_base_directories = submodule_base_directories.to_vec();
+        self
+    }
+
+    pub fn warnings_as_errors(mut self, warnings_as_errors: bool) -> Self {
+        self.options.warnings_as_errors = warnings_as_errors;
+        self
+    }
+
+    pub fn report_data_layout(mut self, report_data_layout: bool) -> Self {
+        self.options.report_data_layout = report_data_layout;
+        self
+    }
+
+    pub fn verify_external_functions(mut self, verify_external_functions: bool) -> Self {
+        self.options.verify_external_functions = verify_external_functions;
+        self
+    }
+
+    pub fn system_library_search_directories(
+        mut self,
+        system_library_search_directories: &[PathBuf],
+    ) -> Self {
+        self.options.system_library_search_directories = system_library_search_directories.to_vec();
+        self
+    }
+
+    pub fn link(&self) -> Result<StaticLinkOutput, LinkerError> {
+        let options = &self.options;
+
+        let (mut image_common_entry, mut warnings, unordered_data_section_sizes) = link_submodules(
+            &self.target_module_name,
+            &self.target_module_version,
+            options,
+            self.submodule_entries,
+        )?;
+
+        if !options.export_filters.is_empty() {
+            warnings.extend(apply_export_filters(
+                &mut image_common_entry,
+                &options.export_filters,
+            ));
+        }
+
+        let is_finalized = options.image_type != ImageType::ObjectFile;
+
+        let removed_function_names = if options.remove_unused_functions && is_finalized {
+            remove_unused_functions(&mut image_common_entry)
+        } else {
+            vec![]
+        };
+
+        if options.strip && is_finalized {
+            strip_image_common_entry(&mut image_common_entry);
+        }
+
+        if options.verify_external_functions {
+            let system_library_search_directories =
+                if options.system_library_search_directories.is_empty() {
+                    get_default_library_search_directories()
+                } else {
+                    options.system_library_search_directories.clone()
+                };
+
+            verify_external_functions(
+                &image_common_entry.external_library_entries,
+                &image_common_entry.external_function_entries,
+                &system_library_search_directories,
+            )?;
+        }
+
+        if options.warnings_as_errors {
+            if let Some(warning) = warnings.first() {
+                return Err(LinkerError::new(LinkErrorType::WarningAsError(
+                    warning.clone(),
+                )));
+            }
+        }
+
+        let data_layout_report_opt = options.report_data_layout.then(|| {
+            if options.optimize_data_layout {
+                DataLayoutReport {
+                    before: unordered_data_section_sizes,
+                    after: calculate_data_section_sizes(
+                        &image_common_entry.read_only_data_entries,
+                        &image_common_entry.read_write_data_entries,
+                        &image_common_entry.uninit_data_entries,
+                    ),
+                }
+            } else {
+                build_data_layout_report(&image_common_entry)
+            }
+        });
+
+        Ok(StaticLinkOutput {
+            image_common_entry,
+            warnings,
+            removed_function_names,
+            data_layout_report_opt,
+        })
+    }
+}
+
 /// Merges submodules or modules.
 ///
 /// When statically linking different modules (non-submodules), if they
 /// both reference the same "Local" module but use different paths (relative paths),
-/// the link will fail. Also, if a "Remote" module is referenced but from
+/// the paths are normalized against the base directory of each submodule
+/// before comparing, the link fails only if they point to different directories.
+/// Also, if a "Remote" module is referenced but from
 /// different source, the link will also fail.
 /// So when statically linking different modules, it is recommended to use only
 /// "Share" and "Runtime" type dependencies. "Local" and "Remote" dependencies
 /// should only be considered for internal development and testing purposes.
+///
+/// This is a shortcut of `StaticLinker`, which outputs a shared module
+/// when `finalize_internal_functions_reference` is true, otherwise an object file.
+/// Use `StaticLinker` for the other options.
 pub fn static_link(
     target_module_name: &str,
     target_module_version: &EffectiveVersion,
@@ -59,7 +381,90 @@ pub fn static_link(
     // all internal functon and data references need to be resolved.
     finalize_internal_functions_reference: bool,
     submodule_entries: &[ImageCommonEntry],
-) -> Result<ImageCommonEntry, LinkerError> {
+) -> Result<(ImageCommonEntry, Vec<LinkerWarning>), LinkerError> {
+    let image_type = if finalize_internal_functions_reference {
+        ImageType::SharedModule
+    } else {
+        ImageType::ObjectFile
+    };
+
+    let output = StaticLinker::new(target_module_name, target_module_version, submodule_entries)
+        .image_type(image_type)
+        .link()?;
+
+    Ok((output.image_common_entry, output.warnings))
+}
+
+/// Merges the submodules into the output module, the post-processing
+/// (e.g. export filters, removing unused functions and stripping)
+/// is done by `StaticLinker::link`.
+///
+/// The sizes of data sections before `layout_data_entries` are returned
+/// as well, they are used by the data layout report.
+#[allow(clippy::type_complexity)]
+fn link_submodules(
+    target_module_name: &str,
+    target_module_version: &EffectiveVersion,
+    options: &StaticLinkOptions,
+    submodule_entries: &[ImageCommonEntry],
+) -> Result<
+    (
+        ImageCommonEntry,
+        Vec<LinkerWarning>,
+        /* data section sizes before layout */ DataSectionSizes,
+    ),
+    LinkerError,
+> {
+    let active_flags = options
+        .active_flags
+        .iter()
+        .map(|item| item.as_str())
+        .collect::<Vec<_>>();
+    let active_flags = active_flags.as_slice();
+    let submodule_base_directories = options.submodule_base_directories.as_slice();
+
+    // a missing base directory would silently fall back to the directory
+    // of the output module, and produce wrongly normalized paths.
+    if !submodule_base_directories.is_empty()
+        && submodule_base_directories.len() != submodule_entries.len()
+    {
+        return Err(LinkerError::new(
+            LinkErrorType::SubmoduleBaseDirectoryCountMismatch(
+                submodule_entries.len(),
+                submodule_base_directories.len(),
+            ),
+        ));
+    }
+
+    // all internal functon and data references need to be resolved
+    // when the output is not an object file.
+    let finalize_internal_functions_reference = options.image_type != ImageType::ObjectFile;
+
+    check_inactive_dependency_references(submodule_entries, active_flags)?;
+
+    // the relocation lists of stripped modules are removed,
+    // so they cannot be linked again.
+    if let Some(stripped_entry) = submodule_entries
+        .iter()
+        .find(|item| item.relocate_list_entries.len() != item.function_entries.len())
+    {
+        return Err(LinkerError::new(LinkErrorType::StrippedModuleRelinked(
+            stripped_entry.name.to_owned(),
+        )));
+    }
+
+    // only object files (i.e. the output of the assembler or the static linker
+    // without finalizing) can be linked.
+    if let Some(unexpected_entry) = submodule_entries
+        .iter()
+        .find(|item| item.image_type != ImageType::ObjectFile)
+    {
+        return Err(LinkerError::new(LinkErrorType::UnexpectedImageType(
+            unexpected_entry.name.to_owned(),
+            unexpected_entry.image_type,
+        )));
+    }
+
     // merge type entries
     let type_entries_list = submodule_entries
         .iter()
@@ -80,8 +485,17 @@ pub fn static_link(
         .iter()
         .map(|item| item.import_module_entries.as_slice())
         .collect::<Vec<_>>();
-    let (import_module_entries, import_module_remap_indices_list) =
-        merge_import_module_entries(&import_module_entries_list)?;
+    let submodule_names = submodule_entries
+        .iter()
+        .map(|item| item.name.as_str())
+        .collect::<Vec<_>>();
+    let (import_module_entries, import_module_remap_indices_list, mut warnings) =
+        merge_import_module_entries(
+            &submodule_names,
+            active_flags,
+            submodule_base_directories,
+            &import_module_entries_list,
+        )?;
 
     // merge export data entries and data entries
     let export_data_entries_list = submodule_entries
@@ -112,11 +526,11 @@ pub fn static_link(
     // - internal read-write data items
     // - internal uninitilized data items
     let (
-        export_data_entries,
-        read_only_data_entries,
-        read_write_data_entries,
-        uninit_data_entries,
-        internal_data_remap_indices_list,
+        mut export_data_entries,
+        mut read_only_data_entries,
+        mut read_write_data_entries,
+        mut uninit_data_entries,
+        mut internal_data_remap_indices_list,
     ) = merge_data_entries(
         &export_data_entries_list,
         &read_only_data_entries_list,
@@ -124,6 +538,22 @@ pub fn static_link(
         &uninit_data_entries_list,
     );
 
+    let unordered_data_section_sizes = calculate_data_section_sizes(
+        &read_only_data_entries,
+        &read_write_data_entries,
+        &uninit_data_entries,
+    );
+
+    if options.optimize_data_layout {
+        layout_data_entries(
+            &mut export_data_entries,
+            &mut read_only_data_entries,
+            &mut read_write_data_entries,
+            &mut uninit_data_entries,
+            &mut internal_data_remap_indices_list,
+        );
+    }
+
     // merge import data
     let import_data_entries_list = submodule_entries
         .iter()
@@ -140,6 +570,7 @@ pub fn static_link(
     let (import_data_entries, data_public_remap_indices_list) = merge_import_data_entries(
         &export_data_entries,
         &internal_data_remap_indices_list,
+        &import_module_entries_list,
         &import_module_remap_indices_list,
         &import_data_entries_list,
     )?;
@@ -150,19 +581,26 @@ pub fn static_link(
         .map(|item| item.external_library_entries.as_slice())
         .collect::<Vec<_>>();
     let (external_library_entries, external_library_remap_indices_list) =
-        merge_external_library_entries(&external_library_entries_list)?;
+        merge_external_library_entries(
+            active_flags,
+            submodule_base_directories,
+            &external_library_entries_list,
+        )?;
 
     // merge external functions
     let external_function_entries_list = submodule_entries
         .iter()
         .map(|item| item.external_function_entries.as_slice())
         .collect::<Vec<_>>();
-    let (external_function_entries, external_function_remap_indices_list) =
+    let (external_function_entries, external_function_remap_indices_list, external_warnings) =
         merge_external_function_entries(
+            options.external_symbol_namespace,
+            &external_library_entries_list,
             &external_library_remap_indices_list,
             &type_remap_indices_list,
             &external_function_entries_list,
-        );
+        )?;
+    warnings.extend(external_warnings);
 
     // merge function name entries
     let mut export_function_entries: Vec<ExportFunctionEntry> = vec![];
@@ -176,6 +614,37 @@ pub fn static_link(
         export_function_entries.extend(submodule_entry.export_function_entries.to_vec());
     }
 
+    // reorder functions by the profile, all function public indices are
+    // updated through the remap tables.
+    let function_order_opt = if options.function_profile_entries.is_empty() {
+        None
+    } else {
+        let function_full_names = export_function_entries
+            .iter()
+            .map(|item| item.full_name.as_str())
+            .collect::<Vec<_>>();
+        let function_order =
+            calculate_function_order(&function_full_names, &options.function_profile_entries);
+
+        let mut new_internal_indices = vec![0; function_order.len()];
+        for (new_index, old_index) in function_order.iter().enumerate() {
+            new_internal_indices[*old_index] = new_index;
+        }
+
+        export_function_entries = function_order
+            .iter()
+            .map(|index| export_function_entries[*index].clone())
+            .collect();
+
+        for indices in internal_function_remap_indices_list.iter_mut() {
+            for index in indices.iter_mut() {
+                *index = new_internal_indices[*index];
+            }
+        }
+
+        Some(function_order)
+    };
+
     // merge import function entries
     let import_function_entries_list = submodule_entries
         .iter()
@@ -185,6 +654,7 @@ pub fn static_link(
         merge_import_function_entries(
             &export_function_entries,
             &internal_function_remap_indices_list,
+            &import_module_entries_list,
             &import_module_remap_indices_list,
             &type_remap_indices_list,
             &import_function_entries_list,
@@ -215,12 +685,33 @@ pub fn static_link(
         remap_table_list.push(remap_table);
     }
 
-    let (function_entries, relocate_list_entries) = merge_function_entries(
+    let (mut function_entries, mut relocate_list_entries) = merge_function_entries(
         &relocate_list_entries_list,
         &function_entries_list,
         &remap_table_list,
     );
 
+    if let Some(function_order) = function_order_opt {
+        function_entries = function_order
+            .iter()
+            .map(|index| function_entries[*index].clone())
+            .collect();
+        relocate_list_entries = function_order
+            .iter()
+            .map(|index| relocate_list_entries[*index].clone())
+            .collect();
+    }
+
+    if options.inline_functions {
+        inline_trivial_functions(
+            &import_function_entries,
+            &type_entries,
+            &local_variable_list_entries,
+            &mut function_entries,
+            &mut relocate_list_entries,
+        );
+    }
+
     // Check that the internally referenced functions and data have all been resolved.
     if finalize_internal_functions_reference {
         let the_current_module = ImportModuleEntry::self_reference_entry();
@@ -247,16 +738,18 @@ pub fn static_link(
         }
     }
 
-    let image_type = if finalize_internal_functions_reference {
-        ImageType::SharedModule
-    } else {
-        ImageType::ObjectFile
-    };
+    warnings.extend(find_unused_dependencies(
+        &import_module_entries,
+        &import_function_entries,
+        &import_data_entries,
+        &external_library_entries,
+        &external_function_entries,
+    ));
 
     let merged_image_common_entry = ImageCommonEntry {
         name: target_module_name.to_owned(),
         version: *target_module_version,
-        image_type,
+        image_type: options.image_type,
         import_module_entries,
         import_function_entries,
         import_data_entries,
@@ -273,115 +766,571 @@ pub fn static_link(
         external_function_entries,
     };
 
-    Ok(merged_image_common_entry)
+    Ok((
+        merged_image_common_entry,
+        warnings,
+        unordered_data_section_sizes,
+    ))
 }
 
-fn merge_type_entries(
-    type_entries_list: &[&[TypeEntry]],
-) -> (
-    /* type_entries */ Vec<TypeEntry>,
-    /* type_remap_indices_list */ Vec<RemapIndices>,
-) {
-    // copy the first list
-    let mut entries_merged = type_entries_list[0].to_vec();
-    let mut type_remap_indices_list = vec![(0..entries_merged.len()).collect()];
+/// Builds the remap indices of the items which are going to be removed,
+/// the unused items are mapped to `usize::MAX`.
+fn build_remap_indices(used: &[bool]) -> RemapIndices {
+    let mut next_index = 0;
+    used.iter()
+        .map(|is_used| {
+            if *is_used {
+                next_index += 1;
+                next_index - 1
+            } else {
+                usize::MAX
+            }
+        })
+        .collect()
+}
 
-    // merge remains
-    for entries_source in &type_entries_list[1..] {
-        let mut indices = vec![];
+fn retain_used_entries<T>(entries: Vec<T>, used: &[bool]) -> Vec<T> {
+    entries
+        .into_iter()
+        .zip(used.iter())
+        .filter(|(_, is_used)| **is_used)
+        .map(|(entry, _)| entry)
+        .collect()
+}
 
-        // check each entry
-        for entry_source in *entries_source {
-            let pos_merged_opt = entries_merged.iter().position(|item| {
-                item.params == entry_source.params && item.results == entry_source.results
-            });
+/// Removes the relocation lists, and the type entries and local variable lists
+/// which are not referenced by any function, import function, external function
+/// or instruction, the remaining entries are renumbered.
+fn strip_image_common_entry(image_common_entry: &mut ImageCommonEntry) {
+    let mut type_used = vec![false; image_common_entry.type_entries.len()];
+    let mut local_variable_list_used =
+        vec![false; image_common_entry.local_variable_list_entries.len()];
+
+    let read_code_value = |code: &[u8], code_offset: usize| {
+        let value_data: [u8; 4] = code[code_offset..code_offset + 4].try_into().unwrap();
+        u32::from_le_bytes(value_data) as usize
+    };
 
-            match pos_merged_opt {
-                Some(pos_merged) => {
-                    // found exists
-                    indices.push(pos_merged);
-                }
-                None => {
-                    // add entry
-                    let pos_new = entries_merged.len();
-                    entries_merged.push(entry_source.to_owned());
-                    indices.push(pos_new);
-                }
+    for (function_entry, relocate_list_entry) in image_common_entry
+        .function_entries
+        .iter()
+        .zip(image_common_entry.relocate_list_entries.iter())
+    {
+        type_used[function_entry.type_index] = true;
+        local_variable_list_used[function_entry.local_variable_list_index] = true;
+
+        // e.g. the type and local variables of "block" instructions
+        for relocate_entry in &relocate_list_entry.relocate_entries {
+            let value = read_code_value(&function_entry.code, relocate_entry.code_offset);
+            match relocate_entry.relocate_type {
+                RelocateType::TypeIndex => type_used[value] = true,
+                RelocateType::LocalVariableListIndex => local_variable_list_used[value] = true,
+                _ => {}
             }
         }
+    }
 
-        type_remap_indices_list.push(indices);
+    for import_function_entry in &image_common_entry.import_function_entries {
+        type_used[import_function_entry.type_index] = true;
     }
 
-    (entries_merged, type_remap_indices_list)
-}
+    for external_function_entry in &image_common_entry.external_function_entries {
+        type_used[external_function_entry.type_index] = true;
+    }
 
-fn merge_local_variable_list_entries(
-    local_variable_list_entries_list: &[&[LocalVariableListEntry]],
-) -> (
-    /* local_variable_list_entries */ Vec<LocalVariableListEntry>,
-    /* local_variable_list_remap_indices_list */ Vec<RemapIndices>,
-) {
-    // copy the first list
-    let mut entries_merged = local_variable_list_entries_list[0].to_vec();
-    let mut local_variable_list_remap_indices_list = vec![(0..entries_merged.len()).collect()];
+    let type_remap_indices = build_remap_indices(&type_used);
+    let local_variable_list_remap_indices = build_remap_indices(&local_variable_list_used);
 
-    // merge remains
-    for entries_source in &local_variable_list_entries_list[1..] {
-        let mut indices = vec![];
+    let relocate_list_entries = std::mem::take(&mut image_common_entry.relocate_list_entries);
 
-        // check each entry
-        for entry_source in entries_source.iter() {
-            let pos_merged_opt = entries_merged.iter().position(|item| {
-                item.local_variable_entries == entry_source.local_variable_entries
-            });
+    for (function_entry, relocate_list_entry) in image_common_entry
+        .function_entries
+        .iter_mut()
+        .zip(relocate_list_entries.iter())
+    {
+        function_entry.type_index = type_remap_indices[function_entry.type_index];
+        function_entry.local_variable_list_index =
+            local_variable_list_remap_indices[function_entry.local_variable_list_index];
+
+        for relocate_entry in &relocate_list_entry.relocate_entries {
+            let remap_indices = match relocate_entry.relocate_type {
+                RelocateType::TypeIndex => &type_remap_indices,
+                RelocateType::LocalVariableListIndex => &local_variable_list_remap_indices,
+                _ => continue,
+            };
 
-            match pos_merged_opt {
-                Some(pos_merged) => {
-                    // found exists
-                    indices.push(pos_merged);
-                }
-                None => {
-                    // add entry
-                    let pos_new = entries_merged.len();
-                    entries_merged.push(entry_source.to_owned());
-                    indices.push(pos_new);
-                }
-            }
+            let code_offset = relocate_entry.code_offset;
+            let value = read_code_value(&function_entry.code, code_offset);
+            function_entry.code[code_offset..code_offset + 4]
+                .copy_from_slice(&(remap_indices[value] as u32).to_le_bytes());
         }
+    }
 
-        local_variable_list_remap_indices_list.push(indices);
+    for import_function_entry in image_common_entry.import_function_entries.iter_mut() {
+        import_function_entry.type_index = type_remap_indices[import_function_entry.type_index];
     }
 
-    (entries_merged, local_variable_list_remap_indices_list)
-}
+    for external_function_entry in image_common_entry.external_function_entries.iter_mut() {
+        external_function_entry.type_index = type_remap_indices[external_function_entry.type_index];
+    }
 
-fn merge_import_module_entries(
-    import_module_entries_list: &[&[ImportModuleEntry]],
-) -> Result<
-    (
-        /* import_module_entries */ Vec<ImportModuleEntry>,
-        /* import_module_remap_indices_list */ Vec<RemapIndices>,
-    ),
-    LinkerError,
-> {
-    // copy the first list
-    let mut entries_merged = import_module_entries_list[0].to_vec();
-    let mut import_module_remap_indices_list = vec![(0..entries_merged.len()).collect()];
+    image_common_entry.type_entries = retain_used_entries(
+        std::mem::take(&mut image_common_entry.type_entries),
+        &type_used,
+    );
 
-    // merge remains
-    for entries_source in &import_module_entries_list[1..] {
-        let mut indices = vec![];
+    image_common_entry.local_variable_list_entries = retain_used_entries(
+        std::mem::take(&mut image_common_entry.local_variable_list_entries),
+        &local_variable_list_used,
+    );
+}
 
-        // check each entry
-        for entry_source in entries_source.iter() {
-            let pos_merged_opt = entries_merged
-                .iter()
-                .position(|item| item.name == entry_source.name);
+/// Checks whether the full name matches the filter, the wildcard "*"
+/// in the filter matches any sequence of characters.
+fn is_export_filter_match(filter: &str, full_name: &str) -> bool {
+    match filter.split_once('*') {
+        None => filter == full_name,
+        Some((prefix, rest)) => {
+            let Some(remain) = full_name.strip_prefix(prefix) else {
+                return false;
+            };
 
-            match pos_merged_opt {
-                Some(pos_merged) => {
-                    let entry_merged = &entries_merged[pos_merged];
+            remain
+                .char_indices()
+                .map(|(pos, _)| pos)
+                .chain(std::iter::once(remain.len()))
+                .any(|pos| is_export_filter_match(rest, &remain[pos..]))
+        }
+    }
+}
+
+/// Demotes the public functions and data which do not match any
+/// export filter to private.
+fn apply_export_filters(
+    image_common_entry: &mut ImageCommonEntry,
+    export_filters: &[String],
+) -> Vec<LinkerWarning> {
+    let is_match = |full_name: &str| {
+        export_filters
+            .iter()
+            .any(|filter| is_export_filter_match(filter, full_name))
+    };
+
+    let mut warnings = vec![];
+
+    for export_function_entry in image_common_entry.export_function_entries.iter_mut() {
+        if export_function_entry.visibility == Visibility::Public
+            && !is_match(&export_function_entry.full_name)
+        {
+            export_function_entry.visibility = Visibility::Private;
+            warnings.push(LinkerWarning::ExportDemoted(
+                export_function_entry.full_name.to_owned(),
+            ));
+        }
+    }
+
+    for export_data_entry in image_common_entry.export_data_entries.iter_mut() {
+        if export_data_entry.visibility == Visibility::Public
+            && !is_match(&export_data_entry.full_name)
+        {
+            export_data_entry.visibility = Visibility::Private;
+            warnings.push(LinkerWarning::ExportDemoted(
+                export_data_entry.full_name.to_owned(),
+            ));
+        }
+    }
+
+    warnings
+}
+
+/// Checks whether the function is an entry point, i.e. the default entry
+/// point, the executable units or the unit tests, see `find_entry_points`
+/// of the dynamic linker for details.
+fn is_entry_point_function(module_name: &str, full_name: &str) -> bool {
+    let Some(path) = full_name
+        .strip_prefix(module_name)
+        .and_then(|item| item.strip_prefix("::"))
+    else {
+        return false;
+    };
+
+    if path == DEFAULT_ENTRY_FUNCTION_NAME {
+        return true;
+    }
+
+    let names = path.split("::").collect::<Vec<_>>();
+    match names.as_slice() {
+        ["app", _, function_name] => *function_name == DEFAULT_ENTRY_FUNCTION_NAME,
+        ["tests", _, function_name] => function_name.starts_with("test_"),
+        _ => false,
+    }
+}
+
+/// Removes the internal functions which are not reachable from the public
+/// functions and the entry points, the remaining functions are renumbered
+/// and the function public indices in the code are updated.
+///
+/// Returns the full names of the removed functions.
+fn remove_unused_functions(image_common_entry: &mut ImageCommonEntry) -> Vec<String> {
+    let import_function_count = image_common_entry.import_function_entries.len();
+    let module_name = image_common_entry.name.clone();
+
+    let read_code_value = |code: &[u8], code_offset: usize| {
+        let value_data: [u8; 4] = code[code_offset..code_offset + 4].try_into().unwrap();
+        u32::from_le_bytes(value_data) as usize
+    };
+
+    // mark the reachable functions, starts from the public functions
+    // and the entry points.
+    let mut function_used = vec![false; image_common_entry.function_entries.len()];
+    let mut pending_internal_indices = image_common_entry
+        .export_function_entries
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| {
+            item.visibility == Visibility::Public
+                || is_entry_point_function(&module_name, &item.full_name)
+        })
+        .map(|(function_internal_index, _)| function_internal_index)
+        .collect::<Vec<_>>();
+
+    while let Some(function_internal_index) = pending_internal_indices.pop() {
+        if function_used[function_internal_index] {
+            continue;
+        }
+        function_used[function_internal_index] = true;
+
+        let function_entry = &image_common_entry.function_entries[function_internal_index];
+        let relocate_list_entry =
+            &image_common_entry.relocate_list_entries[function_internal_index];
+
+        for relocate_entry in &relocate_list_entry.relocate_entries {
+            if relocate_entry.relocate_type == RelocateType::FunctionPublicIndex {
+                let function_public_index =
+                    read_code_value(&function_entry.code, relocate_entry.code_offset);
+                if function_public_index >= import_function_count {
+                    pending_internal_indices.push(function_public_index - import_function_count);
+                }
+            }
+        }
+    }
+
+    let function_remap_indices = build_remap_indices(&function_used);
+
+    // update the function public indices
+    for (function_entry, relocate_list_entry) in image_common_entry
+        .function_entries
+        .iter_mut()
+        .zip(image_common_entry.relocate_list_entries.iter())
+    {
+        for relocate_entry in &relocate_list_entry.relocate_entries {
+            if relocate_entry.relocate_type != RelocateType::FunctionPublicIndex {
+                continue;
+            }
+
+            let code_offset = relocate_entry.code_offset;
+            let function_public_index = read_code_value(&function_entry.code, code_offset);
+            if function_public_index >= import_function_count {
+                let new_function_public_index = import_function_count
+                    + function_remap_indices[function_public_index - import_function_count];
+                function_entry.code[code_offset..code_offset + 4]
+                    .copy_from_slice(&(new_function_public_index as u32).to_le_bytes());
+            }
+        }
+    }
+
+    let removed_function_names = image_common_entry
+        .export_function_entries
+        .iter()
+        .zip(function_used.iter())
+        .filter(|(_, is_used)| !**is_used)
+        .map(|(entry, _)| entry.full_name.to_owned())
+        .collect::<Vec<_>>();
+
+    image_common_entry.function_entries = retain_used_entries(
+        std::mem::take(&mut image_common_entry.function_entries),
+        &function_used,
+    );
+    image_common_entry.export_function_entries = retain_used_entries(
+        std::mem::take(&mut image_common_entry.export_function_entries),
+        &function_used,
+    );
+    image_common_entry.relocate_list_entries = retain_used_entries(
+        std::mem::take(&mut image_common_entry.relocate_list_entries),
+        &function_used,
+    );
+
+    removed_function_names
+}
+
+/// Finds the import modules which nothing is imported from, and the
+/// external libraries which no external function comes from.
+fn find_unused_dependencies(
+    import_module_entries: &[ImportModuleEntry],
+    import_function_entries: &[ImportFunctionEntry],
+    import_data_entries: &[ImportDataEntry],
+    external_library_entries: &[ExternalLibraryEntry],
+    external_function_entries: &[ExternalFunctionEntry],
+) -> Vec<LinkerWarning> {
+    let mut warnings = vec![];
+    let self_reference_module = ImportModuleEntry::self_reference_entry();
+
+    for (import_module_index, import_module_entry) in import_module_entries.iter().enumerate() {
+        if import_module_entry == &self_reference_module {
+            continue;
+        }
+
+        let is_used = import_function_entries
+            .iter()
+            .any(|item| item.import_module_index == import_module_index)
+            || import_data_entries
+                .iter()
+                .any(|item| item.import_module_index == import_module_index);
+
+        if !is_used {
+            warnings.push(LinkerWarning::UnusedImportModule(
+                import_module_entry.name.to_owned(),
+            ));
+        }
+    }
+
+    for (external_library_index, external_library_entry) in
+        external_library_entries.iter().enumerate()
+    {
+        let is_used = external_function_entries
+            .iter()
+            .any(|item| item.external_library_index == external_library_index);
+
+        if !is_used {
+            warnings.push(LinkerWarning::UnusedExternalLibrary(
+                external_library_entry.name.to_owned(),
+            ));
+        }
+    }
+
+    warnings
+}
+
+/// Checks that no function or data is imported from the modules whose
+/// dependency condition is not satisfied, and no external function
+/// comes from such libraries.
+pub fn check_inactive_dependency_references(
+    image_common_entries: &[ImageCommonEntry],
+    active_flags: &[&str],
+) -> Result<(), LinkerError> {
+    for image_common_entry in image_common_entries {
+        let import_module_entries = &image_common_entry.import_module_entries;
+        let external_library_entries = &image_common_entry.external_library_entries;
+
+        for import_function_entry in &image_common_entry.import_function_entries {
+            let import_module_entry =
+                &import_module_entries[import_function_entry.import_module_index];
+            if !is_module_dependency_active(&import_module_entry.module_dependency, active_flags) {
+                return Err(LinkerError::new(
+                    LinkErrorType::InactiveDependencyReferenced(
+                        import_function_entry.full_name.to_owned(),
+                        import_module_entry.name.to_owned(),
+                    ),
+                ));
+            }
+        }
+
+        for import_data_entry in &image_common_entry.import_data_entries {
+            let import_module_entry = &import_module_entries[import_data_entry.import_module_index];
+            if !is_module_dependency_active(&import_module_entry.module_dependency, active_flags) {
+                return Err(LinkerError::new(
+                    LinkErrorType::InactiveDependencyReferenced(
+                        import_data_entry.full_name.to_owned(),
+                        import_module_entry.name.to_owned(),
+                    ),
+                ));
+            }
+        }
+
+        for external_function_entry in &image_common_entry.external_function_entries {
+            let external_library_entry =
+                &external_library_entries[external_function_entry.external_library_index];
+            if !is_external_library_dependency_active(&external_library_entry.value, active_flags) {
+                return Err(LinkerError::new(
+                    LinkErrorType::InactiveDependencyReferenced(
+                        external_function_entry.name.to_owned(),
+                        external_library_entry.name.to_owned(),
+                    ),
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Gets the merged index of the dependency (i.e. an import module or an external
+/// library) which the item comes from, an error is returned if the dependency
+/// has been dropped, see `DroppableRemapIndices`.
+pub(crate) fn get_dependency_remap_index(
+    remap_indices: &DroppableRemapIndices,
+    dependency_index: usize,
+    dependency_name: &str,
+    item_name: &str,
+) -> Result<usize, LinkerError> {
+    remap_indices[dependency_index].ok_or_else(|| {
+        LinkerError::new(LinkErrorType::InactiveDependencyReferenced(
+            item_name.to_owned(),
+            dependency_name.to_owned(),
+        ))
+    })
+}
+
+fn merge_type_entries(
+    type_entries_list: &[&[TypeEntry]],
+) -> (
+    /* type_entries */ Vec<TypeEntry>,
+    /* type_remap_indices_list */ Vec<RemapIndices>,
+) {
+    // copy the first list
+    let mut entries_merged = type_entries_list[0].to_vec();
+    let mut type_remap_indices_list = vec![(0..entries_merged.len()).collect()];
+
+    // merge remains
+    for entries_source in &type_entries_list[1..] {
+        let mut indices = vec![];
+
+        // check each entry
+        for entry_source in *entries_source {
+            let pos_merged_opt = entries_merged.iter().position(|item| {
+                item.params == entry_source.params && item.results == entry_source.results
+            });
+
+            match pos_merged_opt {
+                Some(pos_merged) => {
+                    // found exists
+                    indices.push(pos_merged);
+                }
+                None => {
+                    // add entry
+                    let pos_new = entries_merged.len();
+                    entries_merged.push(entry_source.to_owned());
+                    indices.push(pos_new);
+                }
+            }
+        }
+
+        type_remap_indices_list.push(indices);
+    }
+
+    (entries_merged, type_remap_indices_list)
+}
+
+fn merge_local_variable_list_entries(
+    local_variable_list_entries_list: &[&[LocalVariableListEntry]],
+) -> (
+    /* local_variable_list_entries */ Vec<LocalVariableListEntry>,
+    /* local_variable_list_remap_indices_list */ Vec<RemapIndices>,
+) {
+    // copy the first list
+    let mut entries_merged = local_variable_list_entries_list[0].to_vec();
+    let mut local_variable_list_remap_indices_list = vec![(0..entries_merged.len()).collect()];
+
+    // merge remains
+    for entries_source in &local_variable_list_entries_list[1..] {
+        let mut indices = vec![];
+
+        // check each entry
+        for entry_source in entries_source.iter() {
+            let pos_merged_opt = entries_merged.iter().position(|item| {
+                item.local_variable_entries == entry_source.local_variable_entries
+            });
+
+            match pos_merged_opt {
+                Some(pos_merged) => {
+                    // found exists
+                    indices.push(pos_merged);
+                }
+                None => {
+                    // add entry
+                    let pos_new = entries_merged.len();
+                    entries_merged.push(entry_source.to_owned());
+                    indices.push(pos_new);
+                }
+            }
+        }
+
+        local_variable_list_remap_indices_list.push(indices);
+    }
+
+    (entries_merged, local_variable_list_remap_indices_list)
+}
+
+/// Merges import module entries.
+///
+/// The versions of "Share" dependencies are not compared while merging,
+/// instead, all version requirements are collected and then resolved
+/// by `DependencyVersionResolver`, so the result does not depend on
+/// the order of submodules.
+///
+/// The dependencies whose condition is not satisfied are dropped, and they
+/// do not take part in the conflict checks.
+#[allow(clippy::type_complexity)]
+fn merge_import_module_entries(
+    // the names of submodules, used to report the requesters of
+    // conflicting version requirements.
+    submodule_names: &[&str],
+    active_flags: &[&str],
+    submodule_base_directories: &[PathBuf],
+    import_module_entries_list: &[&[ImportModuleEntry]],
+) -> Result<
+    (
+        /* import_module_entries */ Vec<ImportModuleEntry>,
+        /* import_module_remap_indices_list */ Vec<DroppableRemapIndices>,
+        /* upgrade warnings */ Vec<LinkerWarning>,
+    ),
+    LinkerError,
+> {
+    let mut version_resolver = DependencyVersionResolver::new();
+
+    // collect version requirements
+    for (submodule_name, entries_source) in submodule_names
+        .iter()
+        .zip(import_module_entries_list.iter())
+    {
+        for entry_source in entries_source
+            .iter()
+            .filter(|item| is_module_dependency_active(&item.module_dependency, active_flags))
+        {
+            if let ModuleDependency::Share(share_source) = entry_source.module_dependency.as_ref() {
+                version_resolver.add_requirement(
+                    &entry_source.name,
+                    submodule_name,
+                    &share_source.version,
+                );
+            }
+        }
+    }
+
+    let mut entries_merged: Vec<ImportModuleEntry> = vec![];
+    let mut import_module_remap_indices_list = vec![];
+
+    for (submodule_index, entries_source) in import_module_entries_list.iter().enumerate() {
+        let base_directory = get_base_directory(submodule_base_directories, submodule_index);
+        let mut indices = vec![];
+
+        // check each entry
+        for entry_source in entries_source.iter() {
+            let entry_source = &normalize_import_module_entry(entry_source, base_directory);
+
+            if !is_module_dependency_active(&entry_source.module_dependency, active_flags) {
+                // drop the entry
+                indices.push(None);
+                continue;
+            }
+
+            let pos_merged_opt = entries_merged
+                .iter()
+                .position(|item| item.name == entry_source.name);
+
+            match pos_merged_opt {
+                Some(pos_merged) => {
+                    let entry_merged = &entries_merged[pos_merged];
                     let module_name = &entry_merged.name;
 
                     let dependency_source = entry_source.module_dependency.as_ref();
@@ -422,31 +1371,9 @@ fn merge_import_module_entries(
                                     ));
                                 }
                             }
-                            ModuleDependency::Share(share_source) => {
-                                if let ModuleDependency::Share(share_merged) = dependency_merged {
-                                    // compare version
-                                    match EffectiveVersion::from_str(&share_source.version)
-                                        .compatible(&EffectiveVersion::from_str(
-                                            &share_merged.version,
-                                        )) {
-                                        VersionCompatibility::Equals
-                                        | VersionCompatibility::LessThan => {
-                                            // keep:
-                                            // the target (merged) item is newer than or equals to the source one.
-                                        }
-                                        VersionCompatibility::GreaterThan => {
-                                            // replace:
-                                            // the target (merged) item is older than the source one
-                                            entries_merged[pos_merged] = entry_source.clone()
-                                        }
-                                        VersionCompatibility::Conflict => {
-                                            return Err(LinkerError::new(
-                                                LinkErrorType::DependentVersionConflict(
-                                                    module_name.to_owned(),
-                                                ),
-                                            ));
-                                        }
-                                    }
+                            ModuleDependency::Share(_) => {
+                                if matches!(dependency_merged, ModuleDependency::Share(_)) {
+                                    // the version will be resolved by the version resolver.
                                 } else {
                                     return Err(LinkerError::new(
                                         LinkErrorType::DependentNameConflict(
@@ -468,13 +1395,13 @@ fn merge_import_module_entries(
                         }
                     }
 
-                    indices.push(pos_merged);
+                    indices.push(Some(pos_merged));
                 }
                 None => {
                     // add entry
                     let pos_new = entries_merged.len();
                     entries_merged.push(entry_source.to_owned());
-                    indices.push(pos_new);
+                    indices.push(Some(pos_new));
                 }
             }
         }
@@ -484,13 +1411,54 @@ fn merge_import_module_entries(
         import_module_remap_indices_list.push(indices);
     }
 
-    Ok((entries_merged, import_module_remap_indices_list))
+    let mut warnings = vec![];
+
+    // update the "Share" dependencies to the resolved versions
+    for resolved_version in version_resolver.resolve()? {
+        for requirement in &resolved_version.upgraded_requirements {
+            warnings.push(LinkerWarning::DependencyUpgraded(
+                resolved_version.module_name.to_owned(),
+                requirement.requester.to_owned(),
+                requirement.version.to_owned(),
+                resolved_version.version.to_owned(),
+            ));
+        }
+
+        let pos_merged = entries_merged
+            .iter()
+            .position(|item| item.name == resolved_version.module_name)
+            .unwrap();
+
+        let is_share_with_version = |entry: &ImportModuleEntry| {
+            matches!(
+                entry.module_dependency.as_ref(),
+                ModuleDependency::Share(share) if share.version == resolved_version.version
+            )
+        };
+
+        if !is_share_with_version(&entries_merged[pos_merged]) {
+            // replace with the entry which requires the resolved version.
+            let entry_resolved = import_module_entries_list
+                .iter()
+                .flat_map(|entries| entries.iter())
+                .find(|item| {
+                    item.name == resolved_version.module_name
+                        && is_share_with_version(item)
+                        && is_module_dependency_active(&item.module_dependency, active_flags)
+                })
+                .unwrap();
+            entries_merged[pos_merged] = entry_resolved.to_owned();
+        }
+    }
+
+    Ok((entries_merged, import_module_remap_indices_list, warnings))
 }
 
 fn merge_import_function_entries(
     export_function_entries: &[ExportFunctionEntry],
     internal_function_remap_indices_list: &[RemapIndices],
-    import_module_remap_indices_list: &[RemapIndices],
+    import_module_entries_list: &[&[ImportModuleEntry]],
+    import_module_remap_indices_list: &[DroppableRemapIndices],
     type_remap_indices_list: &[RemapIndices],
     import_function_entries_list: &[&[ImportFunctionEntry]],
 ) -> Result<
@@ -518,8 +1486,13 @@ fn merge_import_function_entries(
 
         // check each entry
         for import_function_entry_source in import_function_entries_source.iter() {
-            let merged_import_module_index = import_module_remap_indices_list[submodule_index]
-                [import_function_entry_source.import_module_index];
+            let import_module_index = import_function_entry_source.import_module_index;
+            let merged_import_module_index = get_dependency_remap_index(
+                &import_module_remap_indices_list[submodule_index],
+                import_module_index,
+                &import_module_entries_list[submodule_index][import_module_index].name,
+                &import_function_entry_source.full_name,
+            )?;
             let merged_type_index =
                 type_remap_indices_list[submodule_index][import_function_entry_source.type_index];
 
@@ -611,24 +1584,205 @@ fn merge_import_function_entries(
     ))
 }
 
-/// the data public index is mixed the following items:
-/// - imported read-only data items
-/// - imported read-write data items
-/// - imported uninitilized data items
-/// - internal read-only data items
-/// - internal read-write data items
-/// - internal uninitilized data items
-#[allow(clippy::type_complexity)]
-fn merge_data_entries(
-    export_data_entries_list: &[&[ExportDataEntry]],
-    read_only_data_entries_list: &[&[InitedDataEntry]],
-    read_write_data_entries_list: &[&[InitedDataEntry]],
-    uninit_data_entries_list: &[&[UninitDataEntry]],
-) -> (
-    /* export_data_entries */ Vec<ExportDataEntry>,
-    /* read_only_data_entries */ Vec<InitedDataEntry>,
-    /* read_write_data_entries */ Vec<InitedDataEntry>,
-    /* uninit_data_entries */ Vec<UninitDataEntry>,
+/// The sizes (in bytes, including the padding) of data sections.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct DataSectionSizes {
+    pub read_only: usize,
+    pub read_write: usize,
+    pub uninit: usize,
+}
+
+impl DataSectionSizes {
+    pub fn total(&self) -> usize {
+        self.read_only + self.read_write + self.uninit
+    }
+}
+
+/// The sizes of data sections before and after the alignment-aware layout.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct DataLayoutReport {
+    pub before: DataSectionSizes,
+    pub after: DataSectionSizes,
+}
+
+impl DataLayoutReport {
+    /// The reduced bytes, it is 0 if the layout does not make the sections smaller.
+    pub fn saved_bytes(&self) -> usize {
+        self.before.total().saturating_sub(self.after.total())
+    }
+}
+
+/// Calculates the size of a section when the items are laid out
+/// in the given order, each item starts at an offset aligned to its alignment.
+fn calculate_section_size(
+    items: impl Iterator<Item = (/* align */ usize, /* length */ usize)>,
+) -> usize {
+    items.fold(0, |offset, (align, length)| {
+        offset.next_multiple_of(align.max(1)) + length
+    })
+}
+
+/// Calculates the order of the items of a section, i.e. the larger alignment
+/// first, and then the larger length first. The result is the original indices
+/// of items in the new order.
+///
+/// Sorting does not always reduce the padding, e.g. the items (align 8, length 1),
+/// (1, 7), (8, 1) take 9 bytes in the original order but 16 bytes after sorting,
+/// so the original order is kept unless the new order is strictly smaller.
+fn calculate_section_layout(items: &[(/* align */ usize, /* length */ usize)]) -> Vec<usize> {
+    let original_order = (0..items.len()).collect::<Vec<_>>();
+
+    let mut order = original_order.clone();
+    order.sort_by_key(|index| {
+        let (align, length) = items[*index];
+        (std::cmp::Reverse(align), std::cmp::Reverse(length))
+    });
+
+    let original_size = calculate_section_size(items.iter().copied());
+    let new_size = calculate_section_size(order.iter().map(|index| items[*index]));
+
+    if new_size < original_size {
+        order
+    } else {
+        original_order
+    }
+}
+
+fn get_inited_data_layout_items(entries: &[InitedDataEntry]) -> Vec<(usize, usize)> {
+    entries
+        .iter()
+        .map(|item| (item.align as usize, item.length as usize))
+        .collect()
+}
+
+fn get_uninit_data_layout_items(entries: &[UninitDataEntry]) -> Vec<(usize, usize)> {
+    entries
+        .iter()
+        .map(|item| (item.align as usize, item.length as usize))
+        .collect()
+}
+
+/// Calculates the sizes of data sections in the current order.
+fn calculate_data_section_sizes(
+    read_only_data_entries: &[InitedDataEntry],
+    read_write_data_entries: &[InitedDataEntry],
+    uninit_data_entries: &[UninitDataEntry],
+) -> DataSectionSizes {
+    DataSectionSizes {
+        read_only: calculate_section_size(
+            get_inited_data_layout_items(read_only_data_entries).into_iter(),
+        ),
+        read_write: calculate_section_size(
+            get_inited_data_layout_items(read_write_data_entries).into_iter(),
+        ),
+        uninit: calculate_section_size(
+            get_uninit_data_layout_items(uninit_data_entries).into_iter(),
+        ),
+    }
+}
+
+/// Reports the sizes of data sections of the given module in the current order
+/// and in the alignment-aware order (see `layout_data_entries`).
+pub fn build_data_layout_report(image_common_entry: &ImageCommonEntry) -> DataLayoutReport {
+    let read_only_items = get_inited_data_layout_items(&image_common_entry.read_only_data_entries);
+    let read_write_items =
+        get_inited_data_layout_items(&image_common_entry.read_write_data_entries);
+    let uninit_items = get_uninit_data_layout_items(&image_common_entry.uninit_data_entries);
+
+    let sizes_in_order = |items: &[(usize, usize)], order: Vec<usize>| {
+        calculate_section_size(order.into_iter().map(|index| items[index]))
+    };
+
+    DataLayoutReport {
+        before: calculate_data_section_sizes(
+            &image_common_entry.read_only_data_entries,
+            &image_common_entry.read_write_data_entries,
+            &image_common_entry.uninit_data_entries,
+        ),
+        after: DataSectionSizes {
+            read_only: sizes_in_order(&read_only_items, calculate_section_layout(&read_only_items)),
+            read_write: sizes_in_order(
+                &read_write_items,
+                calculate_section_layout(&read_write_items),
+            ),
+            uninit: sizes_in_order(&uninit_items, calculate_section_layout(&uninit_items)),
+        },
+    }
+}
+
+/// Reorders the data entries within each section by alignment and size
+/// to minimize the padding (a section is reordered only if it becomes smaller,
+/// see `calculate_section_layout`), and updates the export data entries and
+/// the internal data remap indices to match.
+fn layout_data_entries(
+    export_data_entries: &mut Vec<ExportDataEntry>,
+    read_only_data_entries: &mut Vec<InitedDataEntry>,
+    read_write_data_entries: &mut Vec<InitedDataEntry>,
+    uninit_data_entries: &mut Vec<UninitDataEntry>,
+    internal_data_remap_indices_list: &mut [RemapIndices],
+) {
+    let read_only_order =
+        calculate_section_layout(&get_inited_data_layout_items(read_only_data_entries));
+    let read_write_order =
+        calculate_section_layout(&get_inited_data_layout_items(read_write_data_entries));
+    let uninit_order = calculate_section_layout(&get_uninit_data_layout_items(uninit_data_entries));
+
+    // the internal indices of the old order in the new order,
+    // the sections are in the order of read-only, read-write and uninit.
+    let mut data_internal_order: Vec<usize> = vec![];
+    let mut section_start = 0;
+    for order in [&read_only_order, &read_write_order, &uninit_order] {
+        data_internal_order.extend(order.iter().map(|index| section_start + index));
+        section_start += order.len();
+    }
+
+    let mut new_internal_indices = vec![0; data_internal_order.len()];
+    for (new_index, old_index) in data_internal_order.iter().enumerate() {
+        new_internal_indices[*old_index] = new_index;
+    }
+
+    *read_only_data_entries = read_only_order
+        .iter()
+        .map(|index| read_only_data_entries[*index].clone())
+        .collect();
+    *read_write_data_entries = read_write_order
+        .iter()
+        .map(|index| read_write_data_entries[*index].clone())
+        .collect();
+    *uninit_data_entries = uninit_order
+        .iter()
+        .map(|index| uninit_data_entries[*index].clone())
+        .collect();
+    *export_data_entries = data_internal_order
+        .iter()
+        .map(|index| export_data_entries[*index].clone())
+        .collect();
+
+    for indices in internal_data_remap_indices_list.iter_mut() {
+        for index in indices.iter_mut() {
+            *index = new_internal_indices[*index];
+        }
+    }
+}
+
+/// the data public index is mixed the following items:
+/// - imported read-only data items
+/// - imported read-write data items
+/// - imported uninitilized data items
+/// - internal read-only data items
+/// - internal read-write data items
+/// - internal uninitilized data items
+#[allow(clippy::type_complexity)]
+fn merge_data_entries(
+    export_data_entries_list: &[&[ExportDataEntry]],
+    read_only_data_entries_list: &[&[InitedDataEntry]],
+    read_write_data_entries_list: &[&[InitedDataEntry]],
+    uninit_data_entries_list: &[&[UninitDataEntry]],
+) -> (
+    /* export_data_entries */ Vec<ExportDataEntry>,
+    /* read_only_data_entries */ Vec<InitedDataEntry>,
+    /* read_write_data_entries */ Vec<InitedDataEntry>,
+    /* uninit_data_entries */ Vec<UninitDataEntry>,
     /* internal_data_remap_indices_list */ Vec<RemapIndices>,
 ) {
     let mut export_data_entries: Vec<ExportDataEntry> = vec![];
@@ -714,7 +1868,8 @@ fn merge_data_entries(
 fn merge_import_data_entries(
     export_data_entries: &[ExportDataEntry],
     internal_data_remap_indices_list: &[RemapIndices],
-    import_module_remap_indices_list: &[RemapIndices],
+    import_module_entries_list: &[&[ImportModuleEntry]],
+    import_module_remap_indices_list: &[DroppableRemapIndices],
     import_data_entries_list: &[&[ImportDataEntry]],
 ) -> Result<
     (
@@ -815,8 +1970,14 @@ fn merge_import_data_entries(
                         }
                         None => {
                             // add entry
-                            let merged_import_module_index = import_module_remap_indices_list
-                                [submodule_index][import_data_entry_source.import_module_index];
+                            let import_module_index = import_data_entry_source.import_module_index;
+                            let merged_import_module_index = get_dependency_remap_index(
+                                &import_module_remap_indices_list[submodule_index],
+                                import_module_index,
+                                &import_module_entries_list[submodule_index][import_module_index]
+                                    .name,
+                                &import_data_entry_source.full_name,
+                            )?;
 
                             let pos_new = import_data_entries_merged.len();
                             let import_data_entry_merged = ImportDataEntry::new(
@@ -870,25 +2031,40 @@ fn merge_import_data_entries(
     Ok((import_data_entries_merged, data_public_remap_indices_list))
 }
 
+/// Merges external library entries.
+///
+/// The libraries whose condition is not satisfied are dropped, and they
+/// do not take part in the conflict checks.
 pub fn merge_external_library_entries(
+    active_flags: &[&str],
+    // used to normalize the paths of "Local" libraries,
+    // see `StaticLinkOptions` for details.
+    submodule_base_directories: &[PathBuf],
     external_library_entries_list: &[&[ExternalLibraryEntry]],
 ) -> Result<
     (
         /* external_library_entries */ Vec<ExternalLibraryEntry>,
-        /* external_library_remap_indices_list */ Vec<RemapIndices>,
+        /* external_library_remap_indices_list */ Vec<DroppableRemapIndices>,
     ),
     LinkerError,
 > {
-    // copy the first list
-    let mut entries_merged = external_library_entries_list[0].to_vec();
-    let mut external_library_remap_indices_list = vec![(0..entries_merged.len()).collect()];
+    let mut entries_merged: Vec<ExternalLibraryEntry> = vec![];
+    let mut external_library_remap_indices_list = vec![];
 
-    // merge remains
-    for entries_source in &external_library_entries_list[1..] {
+    for (submodule_index, entries_source) in external_library_entries_list.iter().enumerate() {
+        let base_directory = get_base_directory(submodule_base_directories, submodule_index);
         let mut indices = vec![];
 
         // check each entry
         for entry_source in entries_source.iter() {
+            let entry_source = &normalize_external_library_entry(entry_source, base_directory);
+
+            if !is_external_library_dependency_active(&entry_source.value, active_flags) {
+                // drop the entry
+                indices.push(None);
+                continue;
+            }
+
             let pos_merged_opt = entries_merged
                 .iter()
                 .position(|item| item.name == entry_source.name);
@@ -978,21 +2154,65 @@ pub fn merge_external_library_entries(
                             //         library_name.to_owned(),
                             //     )))
                             // }
-                            ExternalLibraryDependency::System(_) => {
-                                return Err(LinkerError::new(LinkErrorType::DependentNameConflict(
-                                    library_name.to_owned(),
-                                )))
+                            ExternalLibraryDependency::System(value_source) => {
+                                if let ExternalLibraryDependency::System(value_merged) =
+                                    dependency_merged
+                                {
+                                    // compare soname and version, e.g.
+                                    // "libz.so.1" and "libz.so.1.3" are compatible.
+                                    let (file_name_source, version_source) =
+                                        parse_system_library_value(value_source);
+                                    let (file_name_merged, version_merged) =
+                                        parse_system_library_value(value_merged);
+
+                                    if file_name_source != file_name_merged {
+                                        return Err(LinkerError::new(
+                                            LinkErrorType::DependentSourceConflict(
+                                                library_name.to_owned(),
+                                            ),
+                                        ));
+                                    }
+
+                                    match compare_system_library_versions(
+                                        &version_source,
+                                        &version_merged,
+                                    ) {
+                                        VersionCompatibility::Equals
+                                        | VersionCompatibility::LessThan => {
+                                            // keep:
+                                            // the target (merged) item is newer than or equals to the source one.
+                                        }
+                                        VersionCompatibility::GreaterThan => {
+                                            // replace:
+                                            // the target (merged) item is older than the source one
+                                            entries_merged[pos_merged] = entry_source.clone()
+                                        }
+                                        VersionCompatibility::Conflict => {
+                                            return Err(LinkerError::new(
+                                                LinkErrorType::DependentVersionConflict(
+                                                    library_name.to_owned(),
+                                                ),
+                                            ));
+                                        }
+                                    }
+                                } else {
+                                    return Err(LinkerError::new(
+                                        LinkErrorType::DependentNameConflict(
+                                            library_name.to_owned(),
+                                        ),
+                                    ));
+                                }
                             }
                         }
                     }
 
-                    indices.push(pos_merged);
+                    indices.push(Some(pos_merged));
                 }
                 None => {
                     // add entry
                     let pos_new = entries_merged.len();
                     entries_merged.push(entry_source.to_owned());
-                    indices.push(pos_new);
+                    indices.push(Some(pos_new));
                 }
             }
         }
@@ -1003,22 +2223,68 @@ pub fn merge_external_library_entries(
     Ok((entries_merged, external_library_remap_indices_list))
 }
 
+/// Gets the base directory of the submodule, the length of the list is
+/// checked by `link_submodules`, an empty list means the directory of
+/// the output module.
+fn get_base_directory(submodule_base_directories: &[PathBuf], submodule_index: usize) -> &Path {
+    submodule_base_directories
+        .get(submodule_index)
+        .map(|item| item.as_path())
+        .unwrap_or(Path::new(""))
+}
+
+/// Returns the entry whose "Local" path is normalized against the base directory.
+fn normalize_import_module_entry(
+    import_module_entry: &ImportModuleEntry,
+    base_directory: &Path,
+) -> ImportModuleEntry {
+    let mut entry = import_module_entry.to_owned();
+    if let ModuleDependency::Local(local) = entry.module_dependency.as_mut() {
+        local.path = normalize_local_dependency_path(base_directory, &local.path);
+    }
+    entry
+}
+
+/// Returns the entry whose "Local" path is normalized against the base directory.
+fn normalize_external_library_entry(
+    external_library_entry: &ExternalLibraryEntry,
+    base_directory: &Path,
+) -> ExternalLibraryEntry {
+    let mut entry = external_library_entry.to_owned();
+    if let ExternalLibraryDependency::Local(local) = entry.value.as_mut() {
+        local.path = normalize_local_dependency_path(base_directory, &local.path);
+    }
+    entry
+}
+
+#[allow(clippy::type_complexity)]
 fn merge_external_function_entries(
-    external_library_remap_indices_list: &[RemapIndices],
+    external_symbol_namespace: ExternalSymbolNamespace,
+    external_library_entries_list: &[&[ExternalLibraryEntry]],
+    external_library_remap_indices_list: &[DroppableRemapIndices],
     type_remap_indices_list: &[RemapIndices],
     external_function_entries_list: &[&[ExternalFunctionEntry]],
-) -> (
-    /* external_function_entries */ Vec<ExternalFunctionEntry>,
-    /* external_function_remap_indices_list */ Vec<RemapIndices>,
-) {
+) -> Result<
+    (
+        /* external_function_entries */ Vec<ExternalFunctionEntry>,
+        /* external_function_remap_indices_list */ Vec<RemapIndices>,
+        /* warnings */ Vec<LinkerWarning>,
+    ),
+    LinkerError,
+> {
     // note:
     // - when adding new `ExternalFunctionEntry`, the propertries "external_library_index"
     //   and "type_index" need to be updated.
-    // - when merging external functions, the "name" and the "library" are used to
-    //   determine if the functions are the same or not.
+    // - when merging external functions, the "name" and the "library" (depends on
+    //   the `ExternalSymbolNamespace`) are used to determine if the functions
+    //   are the same or not.
 
     let mut entries_merged: Vec<ExternalFunctionEntry> = vec![];
     let mut external_function_remap_indices_list: Vec<RemapIndices> = vec![];
+    let mut warnings: Vec<LinkerWarning> = vec![];
+
+    // the library names of the merged entries, for the warnings.
+    let mut library_names_merged: Vec<&str> = vec![];
 
     // merge external function list
     for (submodule_index, entries_source) in external_function_entries_list.iter().enumerate() {
@@ -1026,28 +2292,55 @@ fn merge_external_function_entries(
 
         // check each entry
         for entry_source in entries_source.iter() {
-            let external_library_index_merged = external_library_remap_indices_list
-                [submodule_index][entry_source.external_library_index];
-
-            // how to determine if two external functions are the same?
-            // Is it just checking the function name like in C/ELF programs,
-            // includes the library name?
-            let pos_merged_opt = entries_merged.iter().position(|item| {
-                item.name == entry_source.name
-                    && item.external_library_index == external_library_index_merged
-            });
+            let external_library_index = entry_source.external_library_index;
+            let library_name =
+                &external_library_entries_list[submodule_index][external_library_index].name;
+            let external_library_index_merged = get_dependency_remap_index(
+                &external_library_remap_indices_list[submodule_index],
+                external_library_index,
+                library_name,
+                &entry_source.name,
+            )?;
+            let type_index_merged =
+                type_remap_indices_list[submodule_index][entry_source.type_index];
+
+            let pos_merged_opt = find_external_function(
+                external_symbol_namespace,
+                &entries_merged,
+                &entry_source.name,
+                external_library_index_merged,
+            );
 
             match pos_merged_opt {
                 Some(pos_merged) => {
                     // found exists
-                    // todo: check declare type
+                    // todo: check declare type in the library-qualified namespace
+                    if external_symbol_namespace == ExternalSymbolNamespace::Global
+                        && entries_merged[pos_merged].type_index != type_index_merged
+                    {
+                        return Err(LinkerError::new(LinkErrorType::ExternalFunctionConflict(
+                            entry_source.name.to_owned(),
+                        )));
+                    }
+
+                    if entries_merged[pos_merged].external_library_index
+                        != external_library_index_merged
+                    {
+                        let warning = LinkerWarning::ExternalFunctionMerged(
+                            entry_source.name.to_owned(),
+                            library_names_merged[pos_merged].to_owned(),
+                            library_name.to_owned(),
+                        );
+                        if !warnings.contains(&warning) {
+                            warnings.push(warning);
+                        }
+                    }
+
                     indices.push(pos_merged);
                 }
                 None => {
                     // add entry
                     let pos_new = entries_merged.len();
-                    let type_index_merged =
-                        type_remap_indices_list[submodule_index][entry_source.type_index];
 
                     let entry_merged = ExternalFunctionEntry::new(
                         entry_source.name.clone(),
@@ -1055,6 +2348,7 @@ fn merge_external_function_entries(
                         type_index_merged,
                     );
                     entries_merged.push(entry_merged);
+                    library_names_merged.push(library_name);
                     indices.push(pos_new);
                 }
             }
@@ -1063,7 +2357,29 @@ fn merge_external_function_entries(
         external_function_remap_indices_list.push(indices);
     }
 
-    (entries_merged, external_function_remap_indices_list)
+    Ok((
+        entries_merged,
+        external_function_remap_indices_list,
+        warnings,
+    ))
+}
+
+/// Finds the merged external function which is the same as the given one,
+/// see `ExternalSymbolNamespace` for details.
+pub(crate) fn find_external_function(
+    external_symbol_namespace: ExternalSymbolNamespace,
+    external_function_entries_merged: &[ExternalFunctionEntry],
+    name: &str,
+    external_library_index_merged: usize,
+) -> Option<usize> {
+    external_function_entries_merged
+        .iter()
+        .position(|item| match external_symbol_namespace {
+            ExternalSymbolNamespace::LibraryQualified => {
+                item.name == name && item.external_library_index == external_library_index_merged
+            }
+            ExternalSymbolNamespace::Global => item.name == name,
+        })
 }
 
 fn merge_function_entries(
@@ -1136,6 +2452,259 @@ fn merge_function_entries(
     (merged_function_entries, merged_relocate_list_entries)
 }
 
+/// The instructions which can be inlined, they are all 8 bytes long
+/// (the same as the "call" instruction) and do not access the local
+/// variables or the control flow of the callee.
+const INLINABLE_OPCODES: [Opcode; 8] = [
+    Opcode::imm_i32,
+    Opcode::imm_f32,
+    Opcode::call,
+    Opcode::data_load_i64,
+    Opcode::data_load_i32_s,
+    Opcode::data_load_i32_u,
+    Opcode::data_load_f64,
+    Opcode::data_load_f32,
+];
+
+/// The data type pushed by each of `INLINABLE_OPCODES`, the results
+/// of "call" depend on the callee.
+const INLINABLE_OPCODE_RESULTS: [Option<OperandDataType>; 8] = [
+    Some(OperandDataType::I32),
+    Some(OperandDataType::F32),
+    None,
+    Some(OperandDataType::I64),
+    Some(OperandDataType::I32),
+    Some(OperandDataType::I32),
+    Some(OperandDataType::F64),
+    Some(OperandDataType::F32),
+];
+
+const CALL_INSTRUCTION_LENGTH: usize = 8;
+
+/// The instructions "local_load_*" are also 8 bytes long, i.e.
+/// opcode (u16) + reversed index (u16) + offset (u16) + local variable index (u16).
+const LOCAL_LOAD_INSTRUCTION_LENGTH: usize = 8;
+
+/// Gets whether the instruction loads a whole local variable of the data type.
+fn is_local_load_opcode_of(opcode: u16, data_type: OperandDataType) -> bool {
+    match data_type {
+        OperandDataType::I32 => {
+            opcode == Opcode::local_load_i32_s as u16 || opcode == Opcode::local_load_i32_u as u16
+        }
+        OperandDataType::I64 => opcode == Opcode::local_load_i64 as u16,
+        OperandDataType::F32 => opcode == Opcode::local_load_f32 as u16,
+        OperandDataType::F64 => opcode == Opcode::local_load_f64 as u16,
+    }
+}
+
+/// Inlines the trivial functions into their callers, i.e. replaces the
+/// instruction "call" with the instruction in the body of the callee.
+///
+/// Two kinds of functions are trivial:
+///
+/// - getters: the function has no parameters and no local variables, and
+///   its body is a single inlinable instruction (see `INLINABLE_OPCODES`)
+///   followed by "end". The instruction should push exactly the results of
+///   the function, e.g. "imm_i32" for "() -> i32", or "call" to a getter
+///   which has the same results.
+/// - forwarding wrappers: the local variables of the function are exactly its
+///   parameters, and its body loads every parameter in order and then calls
+///   another function of the same type (params and results), followed by "end".
+///
+/// In both cases the inlined instruction has the same length as the
+/// instruction "call", so the offsets of the other instructions of the caller
+/// are unchanged and no branch needs to be rewritten. The local variable
+/// slots of a wrapper are remapped to the caller's operand stack: the arguments
+/// pushed by the caller are already in the order which the wrapper loads them,
+/// so the forwarded call consumes them directly and the wrapper frame is not
+/// needed. Functions with any other body (e.g. which compute on the parameters,
+/// reorder them or have control flow) are not inlined, because inlining them
+/// would change the length of the caller.
+///
+/// The relocation entry of the inlined instruction is copied from the callee to
+/// the caller, and the relocation entry of the replaced "call" is removed.
+/// The callees are kept because they may be exported or called indirectly.
+fn inline_trivial_functions(
+    import_function_entries: &[ImportFunctionEntry],
+    type_entries: &[TypeEntry],
+    local_variable_list_entries: &[LocalVariableListEntry],
+    function_entries: &mut [FunctionEntry],
+    relocate_list_entries: &mut [RelocateListEntry],
+) {
+    let import_function_count = import_function_entries.len();
+
+    let read_opcode = |code: &[u8], code_offset: usize| {
+        u16::from_le_bytes([code[code_offset], code[code_offset + 1]])
+    };
+
+    let read_value = |code: &[u8], code_offset: usize| {
+        let value_data: [u8; 4] = code[code_offset..code_offset + 4].try_into().unwrap();
+        u32::from_le_bytes(value_data) as usize
+    };
+
+    let get_function_type = |function_entries: &[FunctionEntry], function_public_index: usize| {
+        let type_index_opt = if function_public_index < import_function_count {
+            Some(import_function_entries[function_public_index].type_index)
+        } else {
+            function_entries
+                .get(function_public_index - import_function_count)
+                .map(|item| item.type_index)
+        };
+        type_index_opt.map(|type_index| &type_entries[type_index])
+    };
+
+    // returns the offset of the instruction to be inlined
+    // if the function is trivial.
+    let find_inlinable_instruction = |function_entries: &[FunctionEntry],
+                                      relocate_list_entries: &[RelocateListEntry],
+                                      function_internal_index: usize|
+     -> Option<usize> {
+        let function_entry = &function_entries[function_internal_index];
+        let type_entry = &type_entries[function_entry.type_index];
+        let code = &function_entry.code;
+
+        // the parameters occupy the first local variable slots.
+        let param_count = type_entry.params.len();
+        let instruction_offset = param_count * LOCAL_LOAD_INSTRUCTION_LENGTH;
+
+        if local_variable_list_entries[function_entry.local_variable_list_index]
+            .local_variable_entries
+            .len()
+            != param_count
+            || code.len() != instruction_offset + CALL_INSTRUCTION_LENGTH + 2
+            || read_opcode(code, instruction_offset + CALL_INSTRUCTION_LENGTH) != Opcode::end as u16
+        {
+            return None;
+        }
+
+        // the parameters are loaded in order, i.e. "local_load_* rev:0 off:0 idx:N"
+        let is_params_forwarded =
+            type_entry
+                .params
+                .iter()
+                .enumerate()
+                .all(|(param_index, data_type)| {
+                    let code_offset = param_index * LOCAL_LOAD_INSTRUCTION_LENGTH;
+                    is_local_load_opcode_of(read_opcode(code, code_offset), *data_type)
+                        && read_opcode(code, code_offset + 2) == 0
+                        && read_opcode(code, code_offset + 4) == 0
+                        && read_opcode(code, code_offset + 6) as usize == param_index
+                });
+
+        if !is_params_forwarded {
+            return None;
+        }
+
+        // the instruction should push exactly the results of the function.
+        let opcode = read_opcode(code, instruction_offset);
+        let is_result_matched = match INLINABLE_OPCODES
+            .iter()
+            .position(|item| *item as u16 == opcode)
+            .map(|opcode_index| INLINABLE_OPCODE_RESULTS[opcode_index])
+        {
+            None => false,
+            Some(Some(data_type)) => param_count == 0 && type_entry.results == [data_type],
+            Some(None) => {
+                // the instruction "call", the callee should take the same
+                // arguments and have the same results.
+                get_function_type(function_entries, read_value(code, instruction_offset + 4))
+                    .is_some_and(|item| {
+                        item.params == type_entry.params && item.results == type_entry.results
+                    })
+            }
+        };
+
+        if !is_result_matched {
+            return None;
+        }
+
+        // the relocation (if any) should be the parameter of the instruction,
+        // and should not refer to the function itself (i.e. recursion).
+        let is_relocation_inlinable = relocate_list_entries[function_internal_index]
+            .relocate_entries
+            .iter()
+            .all(|item| match item.relocate_type {
+                RelocateType::DataPublicIndex => item.code_offset == instruction_offset + 4,
+                RelocateType::FunctionPublicIndex => {
+                    item.code_offset == instruction_offset + 4
+                        && read_value(code, instruction_offset + 4)
+                            != import_function_count + function_internal_index
+                }
+                _ => false,
+            });
+
+        is_relocation_inlinable.then_some(instruction_offset)
+    };
+
+    // repeat until no change, so the chains of wrappers are inlined.
+    let mut changed = true;
+    while changed {
+        changed = false;
+
+        for caller_index in 0..function_entries.len() {
+            let mut relocate_entries = relocate_list_entries[caller_index].relocate_entries.clone();
+
+            for relocate_entry_index in 0..relocate_entries.len() {
+                let relocate_entry = &relocate_entries[relocate_entry_index];
+                let code = &function_entries[caller_index].code;
+
+                if relocate_entry.relocate_type != RelocateType::FunctionPublicIndex
+                    || relocate_entry.code_offset < 4
+                    || read_opcode(code, relocate_entry.code_offset - 4) != Opcode::call as u16
+                {
+                    continue;
+                }
+
+                let function_public_index = read_value(code, relocate_entry.code_offset);
+                if function_public_index < import_function_count {
+                    continue;
+                }
+
+                let callee_index = function_public_index - import_function_count;
+                if callee_index == caller_index {
+                    continue;
+                }
+
+                let Some(callee_instruction_offset) = find_inlinable_instruction(
+                    function_entries,
+                    relocate_list_entries,
+                    callee_index,
+                ) else {
+                    continue;
+                };
+
+                // replace the instruction
+                let instruction_offset = relocate_entry.code_offset - 4;
+                let callee_instruction = function_entries[callee_index].code
+                    [callee_instruction_offset
+                        ..callee_instruction_offset + CALL_INSTRUCTION_LENGTH]
+                    .to_vec();
+                function_entries[caller_index].code
+                    [instruction_offset..instruction_offset + CALL_INSTRUCTION_LENGTH]
+                    .copy_from_slice(&callee_instruction);
+
+                // replace the relocation entry, the entry of "call" is marked
+                // as removed (by offset `usize::MAX`) to keep the indices unchanged.
+                relocate_entries[relocate_entry_index] =
+                    RelocateEntry::new(usize::MAX, RelocateType::FunctionPublicIndex);
+                for callee_relocate_entry in &relocate_list_entries[callee_index].relocate_entries {
+                    relocate_entries.push(RelocateEntry::new(
+                        instruction_offset + callee_relocate_entry.code_offset
+                            - callee_instruction_offset,
+                        callee_relocate_entry.relocate_type,
+                    ));
+                }
+
+                changed = true;
+            }
+
+            relocate_entries.retain(|item| item.code_offset != usize::MAX);
+            relocate_entries.sort_by_key(|item| item.code_offset);
+            relocate_list_entries[caller_index] = RelocateListEntry::new(relocate_entries);
+        }
+    }
+}
+
 /// the map table of importing items to the merged items.
 ///
 /// e.g.
@@ -1159,7 +2728,7 @@ enum ImportRemapItem {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use std::{collections::HashMap, path::PathBuf};
 
     use pretty_assertions::assert_eq;
 
@@ -1168,20 +2737,29 @@ mod tests {
         bytecode_reader::format_bytecode_as_text,
         entry::{
             ExportDataEntry, ExportFunctionEntry, ExternalFunctionEntry, ExternalLibraryEntry,
-            ImageCommonEntry, ImportModuleEntry, InitedDataEntry, LocalVariableEntry,
-            LocalVariableListEntry, RelocateEntry, RelocateListEntry, TypeEntry, UninitDataEntry,
+            FunctionEntry, ImageCommonEntry, ImportModuleEntry, InitedDataEntry,
+            LocalVariableEntry, LocalVariableListEntry, RelocateEntry, RelocateListEntry,
+            TypeEntry, UninitDataEntry,
         },
-        module_image::{RelocateType, Visibility},
+        module_image::{ImageType, RelocateType, Visibility},
     };
     use anc_isa::{
-        DataSectionType, DependencyCondition, DependencyShare, EffectiveVersion,
+        DataSectionType, DependencyCondition, DependencyLocal, DependencyShare, EffectiveVersion,
         ExternalLibraryDependency, ModuleDependency, OperandDataType,
     };
     use anc_parser_asm::parser::parse_from_str;
 
     use crate::{
-        static_linker::{merge_import_module_entries, static_link},
-        LinkErrorType, LinkerError,
+        dependency_resolver::VersionRequirement,
+        elf_verifier::tests::build_elf_file,
+        function_profile::FunctionProfileEntry,
+        static_linker::{
+            build_data_layout_report, calculate_section_layout, calculate_section_size,
+            get_dependency_remap_index, is_export_filter_match, merge_external_library_entries,
+            merge_import_module_entries, static_link, strip_image_common_entry, StaticLinker,
+        },
+        test_helpers::make_share_dependency,
+        ExternalSymbolNamespace, LinkErrorType, LinkerError, LinkerWarning,
     };
 
     fn assemble_submodules(
@@ -1260,7 +2838,7 @@ fn add(left:i32, right:i32) -> i32 {        // type 2, local 3
 
         let submodules = vec![submodule0, submodule1];
         let submodule_entries = assemble_submodules(&submodules, &[], &[]);
-        let linked_module = static_link(
+        let (linked_module, _) = static_link(
             "merged",
             &EffectiveVersion::new(0, 0, 0),
             true,
@@ -1439,8 +3017,14 @@ fn add(left:i32, right:i32) -> i32 {        // type 2, local 3
             import_module_entries0.as_slice(),
             import_module_entries1.as_slice(),
         ];
-        let (merged_module_entries_list, import_module_remap_indices_list) =
-            merge_import_module_entries(&import_module_entries_list).unwrap();
+        let (merged_module_entries_list, import_module_remap_indices_list, warnings) =
+            merge_import_module_entries(
+                &["hello", "hello::world"],
+                &[],
+                &[],
+                &import_module_entries_list,
+            )
+            .unwrap();
 
         // check merged entries
         let expected_module_entries_list = vec![
@@ -1476,8 +3060,25 @@ fn add(left:i32, right:i32) -> i32 {        // type 2, local 3
         assert_eq!(merged_module_entries_list, expected_module_entries_list);
 
         // check remap list
-        assert_eq!(import_module_remap_indices_list[0], vec![0, 1, 2]);
-        assert_eq!(import_module_remap_indices_list[1], vec![0, 3, 2, 1]);
+        assert_eq!(
+            import_module_remap_indices_list[0],
+            vec![Some(0), Some(1), Some(2)]
+        );
+        assert_eq!(
+            import_module_remap_indices_list[1],
+            vec![Some(0), Some(3), Some(2), Some(1)]
+        );
+
+        // check upgrade warnings
+        assert_eq!(
+            warnings,
+            vec![LinkerWarning::DependencyUpgraded(
+                "encoding".to_owned(),
+                "hello".to_owned(),
+                "2.1.0".to_owned(),
+                "2.2.0".to_owned()
+            )]
+        );
     }
 
     #[test]
@@ -1487,42 +3088,236 @@ fn add(left:i32, right:i32) -> i32 {        // type 2, local 3
 
     #[test]
     fn test_merge_import_module_entries_with_source_conflict() {
-        // todo
-    }
+        let make_local_entry = |name: &str, path: &str| {
+            ImportModuleEntry::new(
+                name.to_owned(),
+                Box::new(ModuleDependency::Local(Box::new(DependencyLocal {
+                    path: path.to_owned(),
+                    condition: DependencyCondition::True,
+                    parameters: HashMap::default(),
+                }))),
+            )
+        };
 
-    #[test]
-    fn test_merge_import_module_entries_with_version_conflict() {
-        // todo
-    }
+        let import_module_entries0 = vec![
+            ImportModuleEntry::self_reference_entry(),
+            make_local_entry("utils", "../lib"),
+        ];
 
-    #[test]
-    fn test_merge_import_data() {
-        let submodule0 = (
-            "hello",
-            r#"
-import readonly data module::middle::d0 type i32
-import data module::middle::d2 type i32
-import uninit data module::middle::d4 type i32
-import readonly data module::base::d1 type i32
-import data module::base::d3 type i32
-import uninit data module::base::d5 type i32
+        // the same directory with a different path
+        let import_module_entries1 = vec![
+            ImportModuleEntry::self_reference_entry(),
+            make_local_entry("utils", "../../../lib"),
+        ];
 
-fn main() {
-    data_load_i32_s(d0)
-    data_load_i32_s(d1)
-    data_load_i32_s(d2)
-    data_load_i32_s(d3)
-    data_load_i32_s(d4)
-    data_load_i32_s(d5)
-}"#,
-        );
+        let import_module_entries_list = vec![
+            import_module_entries0.as_slice(),
+            import_module_entries1.as_slice(),
+        ];
 
-        let submodule1 = (
-            "hello::middle",
-            r#"
-import uninit data module::base::d5 type i32
-import readonly data module::base::d1 type i32
-import data module::base::d3 type i32
+        let submodule_base_directories = vec![PathBuf::from(""), PathBuf::from("pkg/foo")];
+
+        let (merged_module_entries_list, import_module_remap_indices_list, _) =
+            merge_import_module_entries(
+                &["hello", "hello::foo"],
+                &[],
+                &submodule_base_directories,
+                &import_module_entries_list,
+            )
+            .unwrap();
+
+        assert_eq!(
+            merged_module_entries_list,
+            vec![
+                ImportModuleEntry::self_reference_entry(),
+                make_local_entry("utils", "../lib"),
+            ]
+        );
+        assert_eq!(import_module_remap_indices_list[1], vec![Some(0), Some(1)]);
+
+        // a different directory
+        let import_module_entries2 = vec![
+            ImportModuleEntry::self_reference_entry(),
+            make_local_entry("utils", "../../lib"),
+        ];
+
+        let import_module_entries_list = vec![
+            import_module_entries0.as_slice(),
+            import_module_entries2.as_slice(),
+        ];
+
+        let merge_result = merge_import_module_entries(
+            &["hello", "hello::foo"],
+            &[],
+            &submodule_base_directories,
+            &import_module_entries_list,
+        );
+
+        assert!(matches!(
+            merge_result,
+            Err(LinkerError {
+                error_type: LinkErrorType::DependentSourceConflict(name)
+            }) if name == "utils"
+        ));
+    }
+
+    #[test]
+    fn test_merge_import_module_entries_with_version_conflict() {
+        let import_module_entries0 = vec![
+            ImportModuleEntry::self_reference_entry(),
+            make_share_dependency("encoding", "1.1.0"),
+        ];
+
+        let import_module_entries1 = vec![
+            ImportModuleEntry::self_reference_entry(),
+            make_share_dependency("encoding", "1.3.0"),
+        ];
+
+        let import_module_entries2 = vec![
+            ImportModuleEntry::self_reference_entry(),
+            make_share_dependency("encoding", "2.0.0"),
+        ];
+
+        let import_module_entries_list = vec![
+            import_module_entries0.as_slice(),
+            import_module_entries1.as_slice(),
+            import_module_entries2.as_slice(),
+        ];
+
+        let merge_result = merge_import_module_entries(
+            &["hello", "hello::foo", "hello::bar"],
+            &[],
+            &[],
+            &import_module_entries_list,
+        );
+
+        assert!(matches!(
+            merge_result,
+            Err(LinkerError {
+                error_type: LinkErrorType::DependentVersionRequirementConflict(name, requirements)
+            }) if name == "encoding" && requirements == vec![
+                VersionRequirement::new("hello", "1.1.0"),
+                VersionRequirement::new("hello::foo", "1.3.0"),
+                VersionRequirement::new("hello::bar", "2.0.0"),
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_merge_import_module_entries_with_condition() {
+        let make_share_entry = |name: &str, version: &str, condition: DependencyCondition| {
+            ImportModuleEntry::new(
+                name.to_owned(),
+                Box::new(ModuleDependency::Share(Box::new(DependencyShare {
+                    version: version.to_owned(),
+                    condition,
+                    parameters: HashMap::default(),
+                }))),
+            )
+        };
+
+        let import_module_entries0 = vec![
+            ImportModuleEntry::self_reference_entry(),
+            make_share_entry(
+                "encoding",
+                "1.1.0",
+                DependencyCondition::IsTrue("legacy".to_owned()),
+            ),
+            make_share_entry("network", "1.0.1", DependencyCondition::True),
+        ];
+
+        let import_module_entries1 = vec![
+            ImportModuleEntry::self_reference_entry(),
+            make_share_entry("encoding", "2.0.0", DependencyCondition::True),
+        ];
+
+        let import_module_entries_list = vec![
+            import_module_entries0.as_slice(),
+            import_module_entries1.as_slice(),
+        ];
+
+        // the conditional dependency is dropped
+        let (merged_module_entries_list, import_module_remap_indices_list, _) =
+            merge_import_module_entries(
+                &["hello", "hello::world"],
+                &[],
+                &[],
+                &import_module_entries_list,
+            )
+            .unwrap();
+
+        assert_eq!(
+            merged_module_entries_list,
+            vec![
+                ImportModuleEntry::self_reference_entry(),
+                make_share_entry("network", "1.0.1", DependencyCondition::True),
+                make_share_entry("encoding", "2.0.0", DependencyCondition::True),
+            ]
+        );
+
+        assert_eq!(
+            import_module_remap_indices_list[0],
+            vec![Some(0), None, Some(1)]
+        );
+        assert_eq!(import_module_remap_indices_list[1], vec![Some(0), Some(2)]);
+
+        // the dropped dependency can not be referenced
+        assert!(matches!(
+            get_dependency_remap_index(
+                &import_module_remap_indices_list[0],
+                1,
+                "encoding",
+                "encoding::decode"
+            ),
+            Err(LinkerError {
+                error_type: LinkErrorType::InactiveDependencyReferenced(item_name, dependency_name)
+            }) if item_name == "encoding::decode" && dependency_name == "encoding"
+        ));
+
+        // the conditional dependency is satisfied and conflicts
+        let merge_result = merge_import_module_entries(
+            &["hello", "hello::world"],
+            &["legacy"],
+            &[],
+            &import_module_entries_list,
+        );
+
+        assert!(matches!(
+            merge_result,
+            Err(LinkerError {
+                error_type: LinkErrorType::DependentVersionRequirementConflict(name, _)
+            }) if name == "encoding"
+        ));
+    }
+
+    #[test]
+    fn test_merge_import_data() {
+        let submodule0 = (
+            "hello",
+            r#"
+import readonly data module::middle::d0 type i32
+import data module::middle::d2 type i32
+import uninit data module::middle::d4 type i32
+import readonly data module::base::d1 type i32
+import data module::base::d3 type i32
+import uninit data module::base::d5 type i32
+
+fn main() {
+    data_load_i32_s(d0)
+    data_load_i32_s(d1)
+    data_load_i32_s(d2)
+    data_load_i32_s(d3)
+    data_load_i32_s(d4)
+    data_load_i32_s(d5)
+}"#,
+        );
+
+        let submodule1 = (
+            "hello::middle",
+            r#"
+import uninit data module::base::d5 type i32
+import readonly data module::base::d1 type i32
+import data module::base::d3 type i32
 
 readonly data d0:i32 = 0x11
 uninit data d4:i32
@@ -1554,7 +3349,7 @@ fn bar() {
 
         let submodules = vec![submodule0, submodule1, submodule2];
         let submodule_entries = assemble_submodules(&submodules, &[], &[]);
-        let linked_module = static_link(
+        let (linked_module, _) = static_link(
             "merged",
             &EffectiveVersion::new(0, 0, 0),
             true,
@@ -1720,6 +3515,78 @@ fn bar() {
         // todo
     }
 
+    #[test]
+    fn test_merge_external_libraries_with_system_libraries() {
+        let make_system_entry = |name: &str, value: &str| {
+            ExternalLibraryEntry::new(
+                name.to_owned(),
+                Box::new(ExternalLibraryDependency::System(value.to_owned())),
+            )
+        };
+
+        let merge = |values: &[&str]| {
+            let entries = values
+                .iter()
+                .map(|value| vec![make_system_entry("z", value)])
+                .collect::<Vec<_>>();
+            let entries_list = entries
+                .iter()
+                .map(|item| item.as_slice())
+                .collect::<Vec<_>>();
+            merge_external_library_entries(&[], &[], &entries_list)
+        };
+
+        // the compatible versions are merged into the newest one
+        let (external_library_entries, external_library_remap_indices_list) =
+            merge(&["z", "libz.so.1", "libz.so.1.3", "libz.so.1.2.13"]).unwrap();
+        assert_eq!(
+            external_library_entries,
+            vec![make_system_entry("z", "libz.so.1.3")]
+        );
+        assert_eq!(
+            external_library_remap_indices_list,
+            vec![vec![Some(0)], vec![Some(0)], vec![Some(0)], vec![Some(0)]]
+        );
+
+        // the major versions are different
+        assert!(matches!(
+            merge(&["libz.so.1", "libz.so.2"]),
+            Err(LinkerError {
+                error_type: LinkErrorType::DependentVersionConflict(name)
+            }) if name == "z"
+        ));
+
+        // different libraries
+        assert!(matches!(
+            merge(&["libz.so.1", "libzstd.so.1"]),
+            Err(LinkerError {
+                error_type: LinkErrorType::DependentSourceConflict(name)
+            }) if name == "z"
+        ));
+
+        // different types
+        assert!(matches!(
+            merge_external_library_entries(
+                &[],
+                &[],
+                &[
+                    &[make_system_entry("z", "libz.so.1")],
+                    &[ExternalLibraryEntry::new(
+                        "z".to_owned(),
+                        Box::new(ExternalLibraryDependency::Share(Box::new(DependencyShare {
+                            version: "1.0.0".to_owned(),
+                            condition: DependencyCondition::True,
+                            parameters: HashMap::default(),
+                        }))),
+                    )],
+                ]
+            ),
+            Err(LinkerError {
+                error_type: LinkErrorType::DependentNameConflict(name)
+            }) if name == "z"
+        ));
+    }
+
     #[test]
     fn test_merge_external_function() {
         let submodule0 = (
@@ -1763,7 +3630,7 @@ fn bar() -> i32 {
         let submodules = vec![submodule0, submodule1];
         let submodule_entries =
             assemble_submodules(&submodules, &[], &[libabc.clone(), libdef.clone()]);
-        let linked_module = static_link(
+        let (linked_module, _) = static_link(
             "merged",
             &EffectiveVersion::new(0, 0, 0),
             true,
@@ -1862,6 +3729,184 @@ fn bar() -> i32 {
         );
     }
 
+    #[test]
+    fn test_merge_external_function_with_global_namespace() {
+        let submodule0 = (
+            "hello",
+            r#"
+external fn abc::getuid() -> i32
+
+fn main() -> i32 {
+    extcall(getuid)
+}
+"#,
+        );
+
+        let submodule1 = (
+            "hello::world",
+            r#"
+external fn def::getuid() -> i32
+external fn def::getpid() -> i32
+
+fn foo() -> i32 {
+    extcall(getuid)
+}
+
+fn bar() -> i32 {
+    extcall(getpid)
+}
+"#,
+        );
+
+        let libabc = ExternalLibraryEntry::new(
+            "abc".to_owned(),
+            Box::new(ExternalLibraryDependency::System("abc".to_owned())),
+        );
+        let libdef = ExternalLibraryEntry::new(
+            "def".to_owned(),
+            Box::new(ExternalLibraryDependency::System("def".to_owned())),
+        );
+
+        let link = |submodule1_source: &str, external_symbol_namespace| {
+            let submodule_entries = assemble_submodules(
+                &[submodule0, ("hello::world", submodule1_source)],
+                &[],
+                &[libabc.clone(), libdef.clone()],
+            );
+            StaticLinker::new(
+                "merged",
+                &EffectiveVersion::new(0, 0, 0),
+                &submodule_entries,
+            )
+            .image_type(ImageType::SharedModule)
+            .external_symbol_namespace(external_symbol_namespace)
+            .link()
+        };
+
+        // the functions with the same name from different libraries are different
+        let link_output = link(submodule1.1, ExternalSymbolNamespace::LibraryQualified).unwrap();
+        assert!(!link_output
+            .warnings
+            .iter()
+            .any(|warning| matches!(warning, LinkerWarning::ExternalFunctionMerged(..))));
+
+        let linked_module = link_output.image_common_entry;
+        assert_eq!(
+            linked_module
+                .external_function_entries
+                .iter()
+                .map(|item| (item.name.as_str(), item.external_library_index))
+                .collect::<Vec<_>>(),
+            vec![("getuid", 0), ("getuid", 1), ("getpid", 1)]
+        );
+
+        // the functions with the same name are merged into the first one
+        let link_output = link(submodule1.1, ExternalSymbolNamespace::Global).unwrap();
+        assert!(link_output
+            .warnings
+            .contains(&LinkerWarning::ExternalFunctionMerged(
+                "getuid".to_owned(),
+                "abc".to_owned(),
+                "def".to_owned()
+            )));
+
+        let linked_module = link_output.image_common_entry;
+        assert_eq!(
+            linked_module
+                .external_function_entries
+                .iter()
+                .map(|item| (item.name.as_str(), item.external_library_index))
+                .collect::<Vec<_>>(),
+            vec![("getuid", 0), ("getpid", 1)]
+        );
+
+        let func1 = &linked_module.function_entries[1];
+        assert_eq!(
+            format_bytecode_as_text(&func1.code),
+            "\
+0x0000  04 04 00 00  00 00 00 00    extcall           idx:0
+0x0008  c0 03                       end"
+        );
+
+        // the functions with the same name have different types
+        assert!(matches!(
+            link(
+                r#"
+external fn def::getuid(i32) -> i32
+
+fn foo() -> i32 {
+    extcall(getuid, imm_i32(0x11))
+}
+"#,
+                ExternalSymbolNamespace::Global
+            ),
+            Err(LinkerError {
+                error_type: LinkErrorType::ExternalFunctionConflict(name)
+            }) if name == "getuid"
+        ));
+    }
+
+    #[test]
+    fn test_link_with_verify_external_functions() {
+        let library_directory = std::env::temp_dir().join(format!(
+            "anc-linker-static-link-verify-external-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&library_directory).unwrap();
+        let library_search_directories = vec![library_directory.clone()];
+        std::fs::write(
+            library_directory.join("libfoo.so"),
+            build_elf_file(&[("getuid", true)], None),
+        )
+        .unwrap();
+
+        let link = |source: &str| {
+            let submodule_entries = assemble_submodules(
+                &[("hello", source)],
+                &[],
+                &[ExternalLibraryEntry::new(
+                    "foo".to_owned(),
+                    Box::new(ExternalLibraryDependency::System("foo".to_owned())),
+                )],
+            );
+
+            StaticLinker::new("hello", &EffectiveVersion::new(0, 0, 0), &submodule_entries)
+                .image_type(ImageType::SharedModule)
+                .verify_external_functions(true)
+                .system_library_search_directories(&library_search_directories)
+                .link()
+        };
+
+        assert!(link(
+            r#"
+external fn foo::getuid() -> i32
+
+fn main() -> i32 {
+    extcall(getuid)
+}
+"#
+        )
+        .is_ok());
+
+        // the function is not exported by the library
+        assert!(matches!(
+            link(
+                r#"
+external fn foo::getpid() -> i32
+
+fn main() -> i32 {
+    extcall(getpid)
+}
+"#
+            ),
+            Err(LinkerError {
+                error_type: LinkErrorType::ExternalFunctionNotFound(function_name, library_name)
+            }) if function_name == "getpid" && library_name == "foo"
+        ));
+
+        std::fs::remove_dir_all(&library_directory).unwrap();
+    }
+
     #[test]
     fn test_merge_import_function() {
         let submodule0 = (
@@ -1902,7 +3947,7 @@ fn add(left:i32, right:i32)->i32 {
 
         let submodules = vec![submodule0, submodule1, submodule2];
         let submodule_entries = assemble_submodules(&submodules, &[], &[]);
-        let linked_module = static_link(
+        let (linked_module, _) = static_link(
             "merged",
             &EffectiveVersion::new(0, 0, 0),
             true,
@@ -2121,4 +4166,679 @@ data d0:i32 = 0x11
             }) if text == "hello::world::d1"
         ));
     }
+
+    #[test]
+    fn test_link_with_unused_dependencies() {
+        let submodule0 = (
+            "hello",
+            r#"
+import fn std::print(i32)
+external fn foo::getuid() -> i32
+
+fn main() -> i32 {
+    call(print, imm_i32(0x11))
+    extcall(getuid)
+}
+"#,
+        );
+
+        let submodule_entries = assemble_submodules(
+            &[submodule0],
+            &[
+                ImportModuleEntry::new("std".to_owned(), Box::new(ModuleDependency::Runtime)),
+                ImportModuleEntry::new("math".to_owned(), Box::new(ModuleDependency::Runtime)),
+            ],
+            &[
+                ExternalLibraryEntry::new(
+                    "foo".to_owned(),
+                    Box::new(ExternalLibraryDependency::System("foo".to_owned())),
+                ),
+                ExternalLibraryEntry::new(
+                    "bar".to_owned(),
+                    Box::new(ExternalLibraryDependency::System("bar".to_owned())),
+                ),
+            ],
+        );
+
+        let warnings = StaticLinker::new(
+            "merged",
+            &EffectiveVersion::new(0, 0, 0),
+            &submodule_entries,
+        )
+        .image_type(ImageType::SharedModule)
+        .link()
+        .unwrap()
+        .warnings;
+
+        assert_eq!(
+            warnings,
+            vec![
+                LinkerWarning::UnusedImportModule("math".to_owned()),
+                LinkerWarning::UnusedExternalLibrary("bar".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_strip_image_common_entry() {
+        let mut image_common_entry = ImageCommonEntry {
+            name: "hello".to_owned(),
+            version: EffectiveVersion::new(0, 0, 0),
+            image_type: ImageType::SharedModule,
+            import_module_entries: vec![],
+            import_function_entries: vec![],
+            import_data_entries: vec![],
+            type_entries: vec![
+                TypeEntry::new(vec![], vec![]), // unused
+                TypeEntry::new(vec![OperandDataType::I32], vec![OperandDataType::I32]),
+                TypeEntry::new(vec![], vec![OperandDataType::I32]),
+            ],
+            local_variable_list_entries: vec![
+                LocalVariableListEntry::new(vec![LocalVariableEntry::from_i32()]), // unused
+                LocalVariableListEntry::new(vec![]),
+                LocalVariableListEntry::new(vec![LocalVariableEntry::from_i32()]),
+            ],
+            function_entries: vec![FunctionEntry::new(
+                1,
+                1,
+                vec![
+                    0x00, 0x00, 0x00, 0x00, // opcode
+                    0x02, 0x00, 0x00, 0x00, // type index
+                    0x02, 0x00, 0x00, 0x00, // local variable list index
+                ],
+            )],
+            read_only_data_entries: vec![],
+            read_write_data_entries: vec![],
+            uninit_data_entries: vec![],
+            export_function_entries: vec![ExportFunctionEntry::new(
+                "hello::main".to_owned(),
+                Visibility::Public,
+            )],
+            export_data_entries: vec![],
+            relocate_list_entries: vec![RelocateListEntry::new(vec![
+                RelocateEntry::new(0x4, RelocateType::TypeIndex),
+                RelocateEntry::new(0x8, RelocateType::LocalVariableListIndex),
+            ])],
+            external_library_entries: vec![],
+            external_function_entries: vec![],
+        };
+
+        strip_image_common_entry(&mut image_common_entry);
+
+        assert_eq!(
+            image_common_entry.type_entries,
+            vec![
+                TypeEntry::new(vec![OperandDataType::I32], vec![OperandDataType::I32]),
+                TypeEntry::new(vec![], vec![OperandDataType::I32]),
+            ]
+        );
+
+        assert_eq!(
+            image_common_entry.local_variable_list_entries,
+            vec![
+                LocalVariableListEntry::new(vec![]),
+                LocalVariableListEntry::new(vec![LocalVariableEntry::from_i32()]),
+            ]
+        );
+
+        assert_eq!(
+            image_common_entry.function_entries,
+            vec![FunctionEntry::new(
+                0,
+                0,
+                vec![
+                    0x00, 0x00, 0x00, 0x00, // opcode
+                    0x01, 0x00, 0x00, 0x00, // type index
+                    0x01, 0x00, 0x00, 0x00, // local variable list index
+                ],
+            )]
+        );
+
+        assert!(image_common_entry.relocate_list_entries.is_empty());
+    }
+
+    #[test]
+    fn test_link_stripped_module() {
+        let submodule0 = (
+            "hello",
+            r#"
+fn main() -> () {
+    nop()
+}
+"#,
+        );
+
+        let submodule_entries = assemble_submodules(&[submodule0], &[], &[]);
+
+        let stripped_module =
+            StaticLinker::new("hello", &EffectiveVersion::new(0, 0, 0), &submodule_entries)
+                .image_type(ImageType::SharedModule)
+                .strip(true)
+                .link()
+                .unwrap()
+                .image_common_entry;
+
+        assert!(stripped_module.relocate_list_entries.is_empty());
+
+        let link_result = StaticLinker::new(
+            "merged",
+            &EffectiveVersion::new(0, 0, 0),
+            &[stripped_module],
+        )
+        .image_type(ImageType::SharedModule)
+        .link();
+
+        assert!(matches!(
+            link_result,
+            Err(LinkerError {
+                error_type: LinkErrorType::StrippedModuleRelinked(name)
+            }) if name == "hello"
+        ));
+    }
+
+    #[test]
+    fn test_link_with_unexpected_image_type() {
+        let submodule0 = (
+            "hello",
+            r#"
+fn main() -> () {
+    nop()
+}
+"#,
+        );
+
+        let submodule_entries = assemble_submodules(&[submodule0], &[], &[]);
+
+        let shared_module =
+            StaticLinker::new("hello", &EffectiveVersion::new(0, 0, 0), &submodule_entries)
+                .image_type(ImageType::SharedModule)
+                .link()
+                .unwrap()
+                .image_common_entry;
+
+        let link_result =
+            StaticLinker::new("merged", &EffectiveVersion::new(0, 0, 0), &[shared_module])
+                .image_type(ImageType::SharedModule)
+                .link();
+
+        assert!(matches!(
+            link_result,
+            Err(LinkerError {
+                error_type: LinkErrorType::UnexpectedImageType(name, ImageType::SharedModule)
+            }) if name == "hello"
+        ));
+    }
+
+    #[test]
+    fn test_link_with_submodule_base_directory_count_mismatch() {
+        let submodule0 = (
+            "hello",
+            r#"
+fn main() -> () {
+    nop()
+}
+"#,
+        );
+
+        let submodule1 = (
+            "hello::world",
+            r#"
+fn do_this() -> () {
+    nop()
+}
+"#,
+        );
+
+        let submodule_entries = assemble_submodules(&[submodule0, submodule1], &[], &[]);
+
+        let link_result =
+            StaticLinker::new("hello", &EffectiveVersion::new(0, 0, 0), &submodule_entries)
+                .submodule_base_directories(&[PathBuf::from("pkg")])
+                .link();
+
+        assert!(matches!(
+            link_result,
+            Err(LinkerError {
+                error_type: LinkErrorType::SubmoduleBaseDirectoryCountMismatch(2, 1)
+            })
+        ));
+    }
+
+    #[test]
+    fn test_link_with_inline_functions() {
+        let submodule0 = (
+            "hello",
+            r#"
+pub fn get_magic() -> i32 {
+    imm_i32(0x2a)
+}
+
+pub fn forward() -> i32 {
+    call(get_magic)
+}
+
+pub fn inc(num:i32) -> i32 {
+    add_i32(
+        local_load_i32_s(num)
+        imm_i32(1))
+}
+
+fn main() -> i32 {
+    call(inc, call(forward))
+}
+"#,
+        );
+
+        let submodule_entries = assemble_submodules(&[submodule0], &[], &[]);
+
+        let link = |inline_functions: bool| {
+            StaticLinker::new("hello", &EffectiveVersion::new(0, 0, 0), &submodule_entries)
+                .image_type(ImageType::SharedModule)
+                .inline_functions(inline_functions)
+                .link()
+                .unwrap()
+                .image_common_entry
+        };
+
+        let linked_module = link(false);
+        let inlined_module = link(true);
+
+        // the chain "main -> forward -> get_magic" is inlined, but "inc" is not
+        // because it computes on its parameter.
+        let get_magic_code = &inlined_module.function_entries[0].code;
+        let forward_code = &inlined_module.function_entries[1].code;
+        assert_eq!(forward_code, get_magic_code);
+
+        let main_code = &inlined_module.function_entries[3].code;
+        assert_eq!(
+            main_code.len(),
+            linked_module.function_entries[3].code.len()
+        );
+        assert_eq!(&main_code[..8], &get_magic_code[..8]);
+        assert_ne!(main_code, &linked_module.function_entries[3].code);
+
+        // only the relocation entry of "call(inc)" remains.
+        assert_eq!(
+            inlined_module.relocate_list_entries[3]
+                .relocate_entries
+                .iter()
+                .map(|item| item.relocate_type)
+                .collect::<Vec<_>>(),
+            vec![RelocateType::FunctionPublicIndex]
+        );
+
+        assert_eq!(
+            inlined_module.function_entries[2],
+            linked_module.function_entries[2]
+        );
+    }
+
+    #[test]
+    fn test_link_with_inline_forwarding_wrappers() {
+        let submodule0 = (
+            "hello",
+            r#"
+pub fn add(left:i32, right:i32) -> i32 {
+    add_i32(
+        local_load_i32_s(left)
+        local_load_i32_s(right))
+}
+
+pub fn add_wrapper(left:i32, right:i32) -> i32 {
+    call(add, local_load_i32_s(left), local_load_i32_s(right))
+}
+
+pub fn swap_wrapper(left:i32, right:i32) -> i32 {
+    call(add, local_load_i32_s(right), local_load_i32_s(left))
+}
+
+fn main() -> i32 {
+    call(swap_wrapper,
+        call(add_wrapper, imm_i32(0x11), imm_i32(0x13))
+        imm_i32(0x17))
+}
+"#,
+        );
+
+        let submodule_entries = assemble_submodules(&[submodule0], &[], &[]);
+
+        let link = |inline_functions: bool| {
+            StaticLinker::new("hello", &EffectiveVersion::new(0, 0, 0), &submodule_entries)
+                .image_type(ImageType::SharedModule)
+                .inline_functions(inline_functions)
+                .link()
+                .unwrap()
+                .image_common_entry
+        };
+
+        let linked_module = link(false);
+        let inlined_module = link(true);
+
+        // "add_wrapper" forwards its parameters in order, so the call is
+        // redirected to "add", but "swap_wrapper" reorders them so it is kept.
+        assert_eq!(
+            format_bytecode_as_text(&inlined_module.function_entries[3].code),
+            "\
+0x0000  40 01 00 00  11 00 00 00    imm_i32           0x00000011
+0x0008  40 01 00 00  13 00 00 00    imm_i32           0x00000013
+0x0010  00 04 00 00  00 00 00 00    call              idx:0
+0x0018  40 01 00 00  17 00 00 00    imm_i32           0x00000017
+0x0020  00 04 00 00  02 00 00 00    call              idx:2
+0x0028  c0 03                       end"
+        );
+
+        assert_eq!(
+            inlined_module.relocate_list_entries[3],
+            RelocateListEntry::new(vec![
+                RelocateEntry::new(0x14, RelocateType::FunctionPublicIndex),
+                RelocateEntry::new(0x24, RelocateType::FunctionPublicIndex),
+            ])
+        );
+
+        // the wrappers themselves are unchanged
+        assert_eq!(
+            inlined_module.function_entries[..3],
+            linked_module.function_entries[..3]
+        );
+    }
+
+    #[test]
+    fn test_link_with_inline_functions_result_mismatch() {
+        let submodule0 = (
+            "hello",
+            r#"
+pub fn get_magic() -> i64 {
+    imm_i32(0x2a)
+}
+
+pub fn forward() -> i32 {
+    call(get_magic)
+}
+
+fn main() -> i64 {
+    call(get_magic)
+}
+
+fn test() -> i32 {
+    call(forward)
+}
+"#,
+        );
+
+        let submodule_entries = assemble_submodules(&[submodule0], &[], &[]);
+
+        let link = |inline_functions: bool| {
+            StaticLinker::new("hello", &EffectiveVersion::new(0, 0, 0), &submodule_entries)
+                .image_type(ImageType::SharedModule)
+                .inline_functions(inline_functions)
+                .link()
+                .unwrap()
+                .image_common_entry
+        };
+
+        // "get_magic" pushes an i32 but returns an i64, and "forward" returns
+        // a different type from its callee, so nothing is inlined.
+        assert_eq!(link(true).function_entries, link(false).function_entries);
+    }
+
+    #[test]
+    fn test_calculate_section_layout() {
+        // b: 0..8, a: 8..12, c: 12..16
+        assert_eq!(
+            calculate_section_layout(&[(4, 4), (8, 8), (4, 4)]),
+            vec![1, 0, 2]
+        );
+
+        // the original order takes 9 bytes, while the sorted
+        // order takes 16 bytes, so the original order is kept.
+        let items = [(8, 1), (1, 7), (8, 1)];
+        assert_eq!(calculate_section_size(items.iter().copied()), 9);
+        assert_eq!(
+            calculate_section_size([items[0], items[2], items[1]].into_iter()),
+            16
+        );
+        assert_eq!(calculate_section_layout(&items), vec![0, 1, 2]);
+
+        // the same sizes
+        assert_eq!(calculate_section_layout(&[(4, 8), (8, 8)]), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_link_with_optimized_data_layout() {
+        let submodule0 = (
+            "hello",
+            r#"
+uninit data a:i32
+uninit data b:i64
+uninit data c:i32
+
+fn main() -> i64 {
+    data_load_i64(b)
+}
+"#,
+        );
+
+        let submodule_entries = assemble_submodules(&[submodule0], &[], &[]);
+
+        let link = |optimize_data_layout: bool| {
+            StaticLinker::new("hello", &EffectiveVersion::new(0, 0, 0), &submodule_entries)
+                .image_type(ImageType::SharedModule)
+                .optimize_data_layout(optimize_data_layout)
+                .link()
+                .unwrap()
+                .image_common_entry
+        };
+
+        let read_data_index = |module: &ImageCommonEntry| {
+            let relocate_entry = &module.relocate_list_entries[0].relocate_entries[0];
+            assert_eq!(relocate_entry.relocate_type, RelocateType::DataPublicIndex);
+            let offset = relocate_entry.code_offset;
+            let code = &module.function_entries[0].code;
+            u32::from_le_bytes(code[offset..offset + 4].try_into().unwrap())
+        };
+
+        // a: 0..4, b: 8..16, c: 16..20
+        let linked_module = link(false);
+        let report = build_data_layout_report(&linked_module);
+        assert_eq!(report.before.uninit, 20);
+        assert_eq!(report.after.uninit, 16);
+        assert_eq!(report.saved_bytes(), 4);
+        assert_eq!(read_data_index(&linked_module), 1);
+
+        // b: 0..8, a: 8..12, c: 12..16
+        let optimized_module = link(true);
+        let report = build_data_layout_report(&optimized_module);
+        assert_eq!(report.before.uninit, 16);
+        assert_eq!(report.saved_bytes(), 0);
+
+        assert_eq!(
+            optimized_module
+                .export_data_entries
+                .iter()
+                .map(|item| item.full_name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["hello::b", "hello::a", "hello::c"]
+        );
+        assert_eq!(read_data_index(&optimized_module), 0);
+
+        // the report of the linker records the sizes before reordering
+        let version = EffectiveVersion::new(0, 0, 0);
+        let output = StaticLinker::new("hello", &version, &submodule_entries)
+            .image_type(ImageType::SharedModule)
+            .optimize_data_layout(true)
+            .report_data_layout(true)
+            .link()
+            .unwrap();
+        let report = output.data_layout_report_opt.unwrap();
+        assert_eq!(report.before.uninit, 20);
+        assert_eq!(report.after.uninit, 16);
+        assert_eq!(report.saved_bytes(), 4);
+    }
+
+    #[test]
+    fn test_link_with_function_profile() {
+        let submodule0 = (
+            "hello",
+            r#"
+pub fn a() -> i32 {
+    imm_i32(1)
+}
+
+pub fn b() -> i32 {
+    imm_i32(2)
+}
+
+fn main() -> i32 {
+    call(b)
+}
+"#,
+        );
+
+        let submodule_entries = assemble_submodules(&[submodule0], &[], &[]);
+
+        let function_profile_entries = vec![
+            FunctionProfileEntry::new("hello::main".to_owned(), 1),
+            FunctionProfileEntry::new("hello::b".to_owned(), 100),
+        ];
+
+        let linked_module =
+            StaticLinker::new("hello", &EffectiveVersion::new(0, 0, 0), &submodule_entries)
+                .image_type(ImageType::SharedModule)
+                .function_profile_entries(&function_profile_entries)
+                .link()
+                .unwrap()
+                .image_common_entry;
+
+        assert_eq!(
+            linked_module
+                .export_function_entries
+                .iter()
+                .map(|item| item.full_name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["hello::b", "hello::main", "hello::a"]
+        );
+
+        // the function "main" calls "b", whose index is changed from 1 to 0.
+        let relocate_entry = &linked_module.relocate_list_entries[1].relocate_entries[0];
+        assert_eq!(
+            relocate_entry.relocate_type,
+            RelocateType::FunctionPublicIndex
+        );
+
+        let offset = relocate_entry.code_offset;
+        let code = &linked_module.function_entries[1].code;
+        assert_eq!(
+            u32::from_le_bytes(code[offset..offset + 4].try_into().unwrap()),
+            0
+        );
+    }
+
+    #[test]
+    fn test_is_export_filter_match() {
+        assert!(is_export_filter_match("hello::api", "hello::api"));
+        assert!(!is_export_filter_match("hello::api", "hello::api_get"));
+        assert!(is_export_filter_match("hello::api*", "hello::api_get"));
+        assert!(is_export_filter_match("hello::api*", "hello::api"));
+        assert!(is_export_filter_match(
+            "*::get_*",
+            "hello::world::get_magic"
+        ));
+        assert!(!is_export_filter_match("*::get_*", "hello::set_magic"));
+        assert!(is_export_filter_match("*", "hello::main"));
+    }
+
+    #[test]
+    fn test_static_linker_builder() {
+        let submodule0 = (
+            "hello",
+            r#"
+pub fn api_get() -> i32 {
+    call(helper)
+}
+
+fn unused() -> i32 {
+    imm_i32(1)
+}
+
+pub fn other() -> i32 {
+    imm_i32(2)
+}
+
+fn helper() -> i32 {
+    imm_i32(0x2a)
+}
+"#,
+        );
+
+        let submodule_entries = assemble_submodules(&[submodule0], &[], &[]);
+        let version = EffectiveVersion::new(0, 0, 0);
+
+        let output = StaticLinker::new("hello", &version, &submodule_entries)
+            .image_type(ImageType::SharedModule)
+            .remove_unused_functions(true)
+            .export_filters(&["hello::api*"])
+            .report_data_layout(true)
+            .link()
+            .unwrap();
+        let linked_module = &output.image_common_entry;
+
+        assert_eq!(linked_module.image_type, ImageType::SharedModule);
+        assert_eq!(
+            output.warnings,
+            vec![LinkerWarning::ExportDemoted("hello::other".to_owned())]
+        );
+
+        // "other" is demoted to private by the filter, and then
+        // removed together with "unused".
+        assert_eq!(
+            output.removed_function_names,
+            vec!["hello::unused".to_owned(), "hello::other".to_owned()]
+        );
+        assert_eq!(
+            linked_module
+                .export_function_entries
+                .iter()
+                .map(|item| (item.full_name.as_str(), item.visibility))
+                .collect::<Vec<_>>(),
+            vec![
+                ("hello::api_get", Visibility::Public),
+                ("hello::helper", Visibility::Private)
+            ]
+        );
+
+        // the function "api_get" calls "helper", whose index is changed from 3 to 1.
+        let relocate_entry = &linked_module.relocate_list_entries[0].relocate_entries[0];
+        let offset = relocate_entry.code_offset;
+        let code = &linked_module.function_entries[0].code;
+        assert_eq!(
+            u32::from_le_bytes(code[offset..offset + 4].try_into().unwrap()),
+            1
+        );
+
+        assert!(output.data_layout_report_opt.is_some());
+
+        // nothing is removed from the object file
+        let output = StaticLinker::new("hello", &version, &submodule_entries)
+            .remove_unused_functions(true)
+            .link()
+            .unwrap();
+        assert_eq!(output.image_common_entry.image_type, ImageType::ObjectFile);
+        assert!(output.removed_function_names.is_empty());
+        assert_eq!(output.image_common_entry.function_entries.len(), 4);
+        assert!(output.data_layout_report_opt.is_none());
+
+        // the warning fails the linking
+        let result = StaticLinker::new("hello", &version, &submodule_entries)
+            .image_type(ImageType::SharedModule)
+            .export_filters(&["hello::api*"])
+            .warnings_as_errors(true)
+            .link();
+
+        assert!(matches!(
+            result,
+            Err(LinkerError {
+                error_type: LinkErrorType::WarningAsError(LinkerWarning::ExportDemoted(_))
+            })
+        ));
+    }
 }
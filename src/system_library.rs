@@ -0,0 +1,473 @@
+// Copyright (c) 2026 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+use std::path::{Path, PathBuf};
+
+use anc_image::entry::ExternalLibraryEntry;
+use anc_isa::{ExternalLibraryDependency, VersionCompatibility};
+
+use crate::{elf_verifier::read_elf_soname, LinkErrorType, LinkerError};
+
+const DEFAULT_LIBRARY_SEARCH_DIRECTORIES: [&str; 6] = [
+    "/lib",
+    "/usr/lib",
+    "/lib64",
+    "/usr/lib64",
+    "/usr/local/lib",
+    "/usr/local/lib64",
+];
+
+/// Gets the default directories for searching the system libraries, i.e.
+/// the directories in the environment variable "LD_LIBRARY_PATH", followed by
+/// the multiarch directories (e.g. "/usr/lib/x86_64-linux-gnu") and
+/// the standard directories.
+pub fn get_default_library_search_directories() -> Vec<PathBuf> {
+    let mut directories: Vec<PathBuf> = vec![];
+
+    if let Ok(value) = std::env::var("LD_LIBRARY_PATH") {
+        directories.extend(
+            value
+                .split(':')
+                .filter(|item| !item.is_empty())
+                .map(PathBuf::from),
+        );
+    }
+
+    let multiarch = format!("{}-linux-gnu", std::env::consts::ARCH);
+    directories.push(Path::new("/lib").join(&multiarch));
+    directories.push(Path::new("/usr/lib").join(&multiarch));
+    directories.extend(DEFAULT_LIBRARY_SEARCH_DIRECTORIES.iter().map(PathBuf::from));
+
+    directories
+}
+
+/// Parses the version of the file name "lib{name}.so.{version}",
+/// e.g. "libz.so.1.3" is `[1, 3]`.
+fn parse_library_file_version(file_name: &str, prefix: &str) -> Option<Vec<u32>> {
+    file_name
+        .strip_prefix(prefix)?
+        .split('.')
+        .map(|item| item.parse::<u32>().ok())
+        .collect()
+}
+
+/// Splits the value of `ExternalLibraryDependency::System` into the
+/// unversioned file name (or path) and the version, e.g.
+///
+/// - "libz.so.1.3" is `("libz.so", [1, 3])`.
+/// - "/opt/foo/lib/libfoo.so" is `("/opt/foo/lib/libfoo.so", [])`.
+/// - the library name "z" is `("libz.so", [])`.
+pub fn parse_system_library_value(value: &str) -> (String, Vec<u32>) {
+    for (pos, _) in value.match_indices(".so") {
+        let (file_name, suffix) = value.split_at(pos + ".so".len());
+        if suffix.is_empty() {
+            return (file_name.to_owned(), vec![]);
+        }
+
+        if let Some(version) = parse_library_file_version(suffix, ".") {
+            return (file_name.to_owned(), version);
+        }
+    }
+
+    if value.contains('/') {
+        (value.to_owned(), vec![])
+    } else {
+        (format!("lib{}.so", value), vec![])
+    }
+}
+
+/// Compares the versions of two "System" libraries which have
+/// the same file name, the result is the source version compared
+/// with the target one:
+///
+/// - an empty version (i.e. any version) is less than the others.
+/// - the versions with different major numbers (i.e. the first
+///   numbers) are conflicting, because they are ABI incompatible.
+/// - otherwise the versions are compared number by number.
+pub fn compare_system_library_versions(
+    source_version: &[u32],
+    target_version: &[u32],
+) -> VersionCompatibility {
+    if source_version == target_version {
+        return VersionCompatibility::Equals;
+    }
+
+    match (source_version.first(), target_version.first()) {
+        (None, _) => VersionCompatibility::LessThan,
+        (_, None) => VersionCompatibility::GreaterThan,
+        (Some(source_major), Some(target_major)) if source_major != target_major => {
+            VersionCompatibility::Conflict
+        }
+        _ => {
+            if source_version < target_version {
+                VersionCompatibility::LessThan
+            } else {
+                VersionCompatibility::GreaterThan
+            }
+        }
+    }
+}
+
+/// Finds the file of the system library in the search directories,
+/// the value of `ExternalLibraryDependency::System` can be:
+///
+/// - a file path, e.g. "/opt/foo/lib/libfoo.so".
+/// - a file name, e.g. "libz.so.1".
+/// - a library name, e.g. "z", the file "libz.so" (the development symlink)
+///   is preferred, otherwise the file "libz.so.{version}" with the
+///   highest version is selected.
+///
+/// The directories are searched in order, and the first match wins.
+pub fn find_system_library_file(
+    value: &str,
+    library_search_directories: &[PathBuf],
+) -> Option<PathBuf> {
+    if value.contains('/') {
+        let path = PathBuf::from(value);
+        return path.is_file().then_some(path);
+    }
+
+    if value.contains(".so") {
+        return library_search_directories
+            .iter()
+            .map(|directory| directory.join(value))
+            .find(|path| path.is_file());
+    }
+
+    let unversioned_file_name = format!("lib{}.so", value);
+    let versioned_file_name_prefix = format!("lib{}.so.", value);
+
+    library_search_directories.iter().find_map(|directory| {
+        let unversioned_path = directory.join(&unversioned_file_name);
+        if unversioned_path.is_file() {
+            return Some(unversioned_path);
+        }
+
+        std::fs::read_dir(directory)
+            .ok()?
+            .filter_map(|dir_entry| dir_entry.ok())
+            .filter_map(|dir_entry| {
+                let file_name = dir_entry.file_name().to_str()?.to_owned();
+                let version = parse_library_file_version(&file_name, &versioned_file_name_prefix)?;
+                let path = dir_entry.path();
+                path.is_file().then_some((version, path))
+            })
+            .max_by(|left, right| left.0.cmp(&right.0))
+            .map(|(_, path)| path)
+    })
+}
+
+/// Reads the soname of the library file, the file name is
+/// used if the library has no soname.
+pub fn read_system_library_soname(
+    library_name: &str,
+    file_path: &Path,
+) -> Result<String, LinkerError> {
+    let binary = std::fs::read(file_path).map_err(|error| {
+        LinkerError::new(LinkErrorType::InvalidExternalLibrary(
+            library_name.to_owned(),
+            error.to_string(),
+        ))
+    })?;
+
+    let soname_opt = read_elf_soname(&binary).map_err(|message| {
+        LinkerError::new(LinkErrorType::InvalidExternalLibrary(
+            library_name.to_owned(),
+            message,
+        ))
+    })?;
+
+    Ok(soname_opt.unwrap_or_else(|| {
+        file_path
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .into_owned()
+    }))
+}
+
+/// Resolves the "System" libraries against the search directories, and
+/// replaces the value of each `ExternalLibraryDependency::System` with
+/// the soname of the library file (e.g. "z" to "libz.so.1"), so that the
+/// runtime loads exactly the library which is found at link time.
+///
+/// The values which are file paths (e.g. "/opt/foo/lib/libfoo.so") are
+/// replaced with the absolute paths instead, because the runtime does not
+/// search the directories of such libraries.
+///
+/// The other libraries are returned unchanged.
+pub fn resolve_system_library_entries(
+    external_library_entries: &[ExternalLibraryEntry],
+    library_search_directories: &[PathBuf],
+) -> Result<Vec<ExternalLibraryEntry>, LinkerError> {
+    external_library_entries
+        .iter()
+        .map(|external_library_entry| {
+            let ExternalLibraryDependency::System(value) = external_library_entry.value.as_ref()
+            else {
+                return Ok(external_library_entry.to_owned());
+            };
+
+            let library_name = &external_library_entry.name;
+            let file_path = find_system_library_file(value, library_search_directories)
+                .ok_or_else(|| {
+                    LinkerError::new(LinkErrorType::ExternalLibraryNotFound(
+                        library_name.to_owned(),
+                    ))
+                })?;
+
+            // the file is read even if its path is kept,
+            // so that the invalid libraries are reported.
+            let soname = read_system_library_soname(library_name, &file_path)?;
+
+            let resolved_value = if value.contains('/') {
+                std::path::absolute(&file_path)
+                    .map_err(|error| {
+                        LinkerError::new(LinkErrorType::InvalidExternalLibrary(
+                            library_name.to_owned(),
+                            error.to_string(),
+                        ))
+                    })?
+                    .to_string_lossy()
+                    .into_owned()
+            } else {
+                soname
+            };
+
+            Ok(ExternalLibraryEntry::new(
+                library_name.to_owned(),
+                Box::new(ExternalLibraryDependency::System(resolved_value)),
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, path::PathBuf};
+
+    use anc_image::entry::ExternalLibraryEntry;
+    use anc_isa::{
+        DependencyCondition, DependencyLocal, ExternalLibraryDependency, VersionCompatibility,
+    };
+    use pretty_assertions::assert_eq;
+
+    use crate::{elf_verifier::tests::build_elf_file, LinkErrorType, LinkerError};
+
+    use super::{
+        compare_system_library_versions, find_system_library_file, parse_system_library_value,
+        resolve_system_library_entries,
+    };
+
+    #[test]
+    fn test_parse_system_library_value() {
+        assert_eq!(
+            parse_system_library_value("libz.so.1.3"),
+            ("libz.so".to_owned(), vec![1, 3])
+        );
+        assert_eq!(
+            parse_system_library_value("libz.so"),
+            ("libz.so".to_owned(), vec![])
+        );
+        assert_eq!(
+            parse_system_library_value("z"),
+            ("libz.so".to_owned(), vec![])
+        );
+        assert_eq!(
+            parse_system_library_value("/opt/foo/lib/libfoo.so.2"),
+            ("/opt/foo/lib/libfoo.so".to_owned(), vec![2])
+        );
+        assert_eq!(
+            parse_system_library_value("libfoo.sock.so.2"),
+            ("libfoo.sock.so".to_owned(), vec![2])
+        );
+    }
+
+    #[test]
+    fn test_compare_system_library_versions() {
+        assert_eq!(
+            compare_system_library_versions(&[1, 3], &[1, 3]),
+            VersionCompatibility::Equals
+        );
+        assert_eq!(
+            compare_system_library_versions(&[], &[1]),
+            VersionCompatibility::LessThan
+        );
+        assert_eq!(
+            compare_system_library_versions(&[1], &[]),
+            VersionCompatibility::GreaterThan
+        );
+        assert_eq!(
+            compare_system_library_versions(&[1, 2, 13], &[1, 3]),
+            VersionCompatibility::LessThan
+        );
+        assert_eq!(
+            compare_system_library_versions(&[1, 3], &[1]),
+            VersionCompatibility::GreaterThan
+        );
+        assert_eq!(
+            compare_system_library_versions(&[2], &[1, 3]),
+            VersionCompatibility::Conflict
+        );
+    }
+
+    #[test]
+    fn test_find_system_library_file() {
+        let library_directory0 = std::env::temp_dir().join(format!(
+            "anc-linker-system-library-{}-0",
+            std::process::id()
+        ));
+        let library_directory1 = std::env::temp_dir().join(format!(
+            "anc-linker-system-library-{}-1",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&library_directory0).unwrap();
+        std::fs::create_dir_all(&library_directory1).unwrap();
+
+        for file_name in ["libz.so.1", "libz.so.1.3", "libz.so.1.2.13", "libzstd.so.1"] {
+            std::fs::write(library_directory0.join(file_name), b"").unwrap();
+        }
+        std::fs::write(library_directory1.join("libfoo.so"), b"").unwrap();
+        std::fs::write(library_directory1.join("libfoo.so.2"), b"").unwrap();
+
+        let library_search_directories =
+            vec![library_directory0.clone(), library_directory1.clone()];
+
+        // the highest version
+        assert_eq!(
+            find_system_library_file("z", &library_search_directories),
+            Some(library_directory0.join("libz.so.1.3"))
+        );
+
+        // the unversioned file is preferred
+        assert_eq!(
+            find_system_library_file("foo", &library_search_directories),
+            Some(library_directory1.join("libfoo.so"))
+        );
+
+        // the file name
+        assert_eq!(
+            find_system_library_file("libz.so.1", &library_search_directories),
+            Some(library_directory0.join("libz.so.1"))
+        );
+
+        assert_eq!(
+            find_system_library_file("bar", &library_search_directories),
+            None
+        );
+
+        std::fs::remove_dir_all(&library_directory0).unwrap();
+        std::fs::remove_dir_all(&library_directory1).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_system_library_entries() {
+        let library_directory = std::env::temp_dir().join(format!(
+            "anc-linker-system-library-resolve-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&library_directory).unwrap();
+
+        std::fs::write(
+            library_directory.join("libfoo.so"),
+            build_elf_file(&[("getuid", true)], Some("libfoo.so.1")),
+        )
+        .unwrap();
+        std::fs::write(
+            library_directory.join("libbar.so.2"),
+            build_elf_file(&[("getpid", true)], None),
+        )
+        .unwrap();
+        std::fs::write(library_directory.join("libinvalid.so"), b"").unwrap();
+
+        let library_search_directories: Vec<PathBuf> = vec![library_directory.clone()];
+        let path_value = library_directory
+            .join("libfoo.so")
+            .to_str()
+            .unwrap()
+            .to_owned();
+
+        let local_entry = ExternalLibraryEntry::new(
+            "baz".to_owned(),
+            Box::new(ExternalLibraryDependency::Local(Box::new(
+                DependencyLocal {
+                    path: "/not/exists/libbaz.so".to_owned(),
+                    condition: DependencyCondition::True,
+                    parameters: HashMap::default(),
+                },
+            ))),
+        );
+
+        assert_eq!(
+            resolve_system_library_entries(
+                &[
+                    ExternalLibraryEntry::new(
+                        "foo".to_owned(),
+                        Box::new(ExternalLibraryDependency::System("foo".to_owned())),
+                    ),
+                    ExternalLibraryEntry::new(
+                        "bar".to_owned(),
+                        Box::new(ExternalLibraryDependency::System("bar".to_owned())),
+                    ),
+                    ExternalLibraryEntry::new(
+                        "qux".to_owned(),
+                        Box::new(ExternalLibraryDependency::System(path_value.clone())),
+                    ),
+                    local_entry.clone(),
+                ],
+                &library_search_directories
+            )
+            .unwrap(),
+            vec![
+                // the soname
+                ExternalLibraryEntry::new(
+                    "foo".to_owned(),
+                    Box::new(ExternalLibraryDependency::System("libfoo.so.1".to_owned())),
+                ),
+                // the file name
+                ExternalLibraryEntry::new(
+                    "bar".to_owned(),
+                    Box::new(ExternalLibraryDependency::System("libbar.so.2".to_owned())),
+                ),
+                // the path is kept instead of the soname
+                ExternalLibraryEntry::new(
+                    "qux".to_owned(),
+                    Box::new(ExternalLibraryDependency::System(path_value)),
+                ),
+                // unchanged
+                local_entry,
+            ]
+        );
+
+        assert!(matches!(
+            resolve_system_library_entries(
+                &[ExternalLibraryEntry::new(
+                    "hello".to_owned(),
+                    Box::new(ExternalLibraryDependency::System("hello".to_owned())),
+                )],
+                &library_search_directories
+            ),
+            Err(LinkerError {
+                error_type: LinkErrorType::ExternalLibraryNotFound(library_name)
+            }) if library_name == "hello"
+        ));
+
+        assert!(matches!(
+            resolve_system_library_entries(
+                &[ExternalLibraryEntry::new(
+                    "invalid".to_owned(),
+                    Box::new(ExternalLibraryDependency::System("invalid".to_owned())),
+                )],
+                &library_search_directories
+            ),
+            Err(LinkerError {
+                error_type: LinkErrorType::InvalidExternalLibrary(library_name, _)
+            }) if library_name == "invalid"
+        ));
+
+        std::fs::remove_dir_all(&library_directory).unwrap();
+    }
+}
@@ -0,0 +1,90 @@
+// Copyright (c) 2026 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+//! The fixtures shared by the unit tests of the modules.
+
+use std::collections::HashMap;
+
+use anc_assembler::assembler::assemble_module_node;
+use anc_image::{
+    entry::{ImageCommonEntry, ImportModuleEntry},
+    module_image::ImageType,
+};
+use anc_isa::{DependencyCondition, DependencyShare, EffectiveVersion, ModuleDependency};
+use anc_parser_asm::parser::parse_from_str;
+
+use crate::static_linker::StaticLinker;
+
+/// Makes an import module entry of a "Runtime" dependency.
+pub(crate) fn make_runtime_dependency(name: &str) -> ImportModuleEntry {
+    ImportModuleEntry::new(name.to_owned(), Box::new(ModuleDependency::Runtime))
+}
+
+/// Makes an import module entry of a "Share" dependency with
+/// the specified version, no condition and no parameters.
+pub(crate) fn make_share_dependency(name: &str, version: &str) -> ImportModuleEntry {
+    ImportModuleEntry::new(
+        name.to_owned(),
+        Box::new(ModuleDependency::Share(Box::new(DependencyShare {
+            version: version.to_owned(),
+            condition: DependencyCondition::True,
+            parameters: HashMap::default(),
+        }))),
+    )
+}
+
+/// Makes a shared module which contains only the import module entries,
+/// for testing the handling of dependencies.
+pub(crate) fn make_module_entry(
+    name: &str,
+    version: EffectiveVersion,
+    import_module_entries: Vec<ImportModuleEntry>,
+) -> ImageCommonEntry {
+    ImageCommonEntry {
+        name: name.to_owned(),
+        version,
+        image_type: ImageType::SharedModule,
+        import_module_entries,
+        import_function_entries: vec![],
+        import_data_entries: vec![],
+        type_entries: vec![],
+        local_variable_list_entries: vec![],
+        function_entries: vec![],
+        read_only_data_entries: vec![],
+        read_write_data_entries: vec![],
+        uninit_data_entries: vec![],
+        export_function_entries: vec![],
+        export_data_entries: vec![],
+        relocate_list_entries: vec![],
+        external_library_entries: vec![],
+        external_function_entries: vec![],
+    }
+}
+
+/// Assembles the source code as the only submodule and links it
+/// into a shared module.
+pub(crate) fn build_module(
+    module_name: &str,
+    source_code: &str,
+    import_module_entries: &[ImportModuleEntry],
+    version: EffectiveVersion,
+) -> ImageCommonEntry {
+    let module_node = match parse_from_str(source_code) {
+        Ok(node) => node,
+        Err(parser_error) => {
+            panic!("{}", parser_error.with_source(source_code));
+        }
+    };
+
+    let submodule_entry =
+        assemble_module_node(&module_node, module_name, import_module_entries, &[]).unwrap();
+
+    StaticLinker::new(module_name, &version, &[submodule_entry])
+        .image_type(ImageType::SharedModule)
+        .link()
+        .unwrap()
+        .image_common_entry
+}